@@ -0,0 +1,183 @@
+//! Value assembly from token streams
+//!
+//! Turns a JSON-structured token stream into a `serde_json::Value` tree so
+//! format crates don't each reimplement the object/array walk. The token
+//! stream may come from a lenient tokenizer: trailing commas are accepted,
+//! identifiers are treated as strings and numeric keys as key text.
+
+use serde_json::{Map, Number, Value};
+
+use crate::{unescape_json_string, ParseError, Token, TokenType};
+
+/// Maximum container nesting accepted by `assemble_value`
+pub const MAX_ASSEMBLE_DEPTH: usize = 512;
+
+/// Build a `Value` from a token stream holding a single JSON document
+///
+/// String token values are unescaped; number tokens must be JSON number
+/// literals. Structural problems are reported at the offending token's start.
+pub fn assemble_value(tokens: &[Token]) -> Result<Value, ParseError> {
+    let mut assembler = Assembler { tokens, pos: 0 };
+    let value = assembler.value(0)?;
+
+    match assembler.peek() {
+        None => Ok(value),
+        Some(token) if token.token_type == TokenType::EOF => Ok(value),
+        Some(token) => Err(ParseError::new("Unexpected token after value", token.start)),
+    }
+}
+
+struct Assembler<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Assembler<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<&'a Token, ParseError> {
+        let end = self.tokens.last().map_or(0, |t| t.end);
+        match self.tokens.get(self.pos) {
+            Some(token) if token.token_type != TokenType::EOF => {
+                self.pos += 1;
+                Ok(token)
+            }
+            Some(token) => Err(ParseError::new("Unexpected end of input", token.start)),
+            None => Err(ParseError::new("Unexpected end of input", end)),
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, ParseError> {
+        let token = self.next()?;
+        match token.token_type {
+            TokenType::LeftBrace | TokenType::LeftBracket if depth >= MAX_ASSEMBLE_DEPTH => {
+                Err(ParseError::new("Nesting too deep", token.start))
+            }
+            TokenType::LeftBrace => self.object(depth + 1),
+            TokenType::LeftBracket => self.array(depth + 1),
+            TokenType::String => Ok(Value::String(unescape_at(token)?)),
+            TokenType::Identifier => Ok(Value::String(token.value.clone())),
+            TokenType::Number => token
+                .value
+                .parse::<Number>()
+                .map(Value::Number)
+                .map_err(|_| ParseError::new(format!("Invalid number: {}", token.value), token.start)),
+            TokenType::True => Ok(Value::Bool(true)),
+            TokenType::False => Ok(Value::Bool(false)),
+            TokenType::Null => Ok(Value::Null),
+            _ => Err(ParseError::new("Expected a value", token.start)),
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Value, ParseError> {
+        let mut items = Vec::new();
+        loop {
+            if self.peek().map(|t| t.token_type) == Some(TokenType::RightBracket) {
+                self.pos += 1;
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value(depth)?);
+
+            let token = self.next()?;
+            match token.token_type {
+                TokenType::Comma => {}
+                TokenType::RightBracket => return Ok(Value::Array(items)),
+                _ => return Err(ParseError::new("Expected ',' or ']'", token.start)),
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Value, ParseError> {
+        let mut map = Map::new();
+        loop {
+            let token = self.next()?;
+            let key = match token.token_type {
+                TokenType::RightBrace => return Ok(Value::Object(map)),
+                TokenType::String => unescape_at(token)?,
+                TokenType::Identifier | TokenType::Number => token.value.clone(),
+                _ => return Err(ParseError::new("Expected an object key", token.start)),
+            };
+
+            let colon = self.next()?;
+            if colon.token_type != TokenType::Colon {
+                return Err(ParseError::new("Expected ':'", colon.start));
+            }
+            let value = self.value(depth)?;
+            map.insert(key, value);
+
+            let token = self.next()?;
+            match token.token_type {
+                TokenType::Comma => {}
+                TokenType::RightBrace => return Ok(Value::Object(map)),
+                _ => return Err(ParseError::new("Expected ',' or '}'", token.start)),
+            }
+        }
+    }
+}
+
+/// Unescape a string token, positioning errors at the token
+fn unescape_at(token: &Token) -> Result<String, ParseError> {
+    unescape_json_string(&token.value)
+        .map_err(|e| ParseError::new(e.message, token.start + 1 + e.position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn token(token_type: TokenType, value: &str, start: usize) -> Token {
+        Token::new(token_type, value.to_string(), start, start + value.len().max(1))
+    }
+
+    #[test]
+    fn test_assemble_nested() {
+        use TokenType::*;
+        // {"a": [1, {b: true}, "x\n",], "c": null}
+        let tokens = vec![
+            token(LeftBrace, "", 0),
+            token(String, "a", 1),
+            token(Colon, "", 4),
+            token(LeftBracket, "", 6),
+            token(Number, "1", 7),
+            token(Comma, "", 8),
+            token(LeftBrace, "", 10),
+            token(Identifier, "b", 11),
+            token(Colon, "", 12),
+            token(True, "", 14),
+            token(RightBrace, "", 18),
+            token(Comma, "", 19),
+            token(String, "x\\n", 21),
+            token(Comma, "", 26),
+            token(RightBracket, "", 27),
+            token(Comma, "", 28),
+            token(String, "c", 30),
+            token(Colon, "", 33),
+            token(Null, "", 35),
+            token(RightBrace, "", 39),
+            token(EOF, "", 40),
+        ];
+        assert_eq!(
+            assemble_value(&tokens).unwrap(),
+            json!({"a": [1, {"b": true}, "x\n"], "c": null})
+        );
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        use TokenType::*;
+        let missing_colon = vec![
+            token(LeftBrace, "", 0),
+            token(String, "a", 1),
+            token(Number, "1", 5),
+            token(RightBrace, "", 6),
+        ];
+        let err = assemble_value(&missing_colon).unwrap_err();
+        assert_eq!((err.message.as_str(), err.position), ("Expected ':'", 5));
+
+        let unclosed = vec![token(LeftBracket, "", 0), token(Number, "1", 1), token(EOF, "", 2)];
+        assert_eq!(assemble_value(&unclosed).unwrap_err().message, "Unexpected end of input");
+    }
+}
@@ -0,0 +1,97 @@
+//! Identifier case conversion helpers
+
+/// Split an identifier into words
+///
+/// Word boundaries are `_`, `-`, whitespace, a lowercase-to-uppercase
+/// transition (`firstName`) and the end of an acronym (`HTTPServer`).
+pub fn split_words(input: &str) -> Vec<String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch == '_' || ch == '-' || ch.is_whitespace() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if ch.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+
+        current.push(ch);
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+/// Convert to `snake_case`
+pub fn to_snake_case(input: &str) -> String {
+    split_words(input)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Convert to `camelCase`
+pub fn to_camel_case(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+
+    for (i, word) in split_words(input).iter().enumerate() {
+        if i == 0 {
+            result.push_str(&word.to_lowercase());
+        } else {
+            result.push_str(&capitalize(word));
+        }
+    }
+
+    result
+}
+
+/// Convert to `Title Case` (words separated by spaces)
+pub fn to_title_case(input: &str) -> String {
+    split_words(input)
+        .iter()
+        .map(|word| capitalize(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Uppercase the first character and lowercase the rest
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("firstName"), vec!["first", "Name"]);
+        assert_eq!(split_words("HTTPServer_port"), vec!["HTTP", "Server", "port"]);
+        assert_eq!(split_words("user-id 2"), vec!["user", "id", "2"]);
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(to_snake_case("FirstName"), "first_name");
+        assert_eq!(to_camel_case("first_name"), "firstName");
+        assert_eq!(to_title_case("first_name"), "First Name");
+    }
+}
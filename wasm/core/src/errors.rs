@@ -0,0 +1,131 @@
+//! Multiple parse errors
+//!
+//! For features that keep going after the first problem (linting, lenient
+//! cleaning, batch conversion) and report everything they found at once.
+
+use std::fmt;
+
+use crate::{LineIndex, ParseError};
+
+/// A set of parse errors kept in position order
+///
+/// Displays one error per line, as `line:column` when a `LineIndex` has been
+/// attached with `with_lines` and as a byte position otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct ParseErrors {
+    errors: Vec<ParseError>,
+    lines: Option<LineIndex>,
+}
+
+impl ParseErrors {
+    /// Collect errors, sorting them by position (ties keep their order)
+    pub fn new(mut errors: Vec<ParseError>) -> Self {
+        errors.sort_by_key(|e| e.position);
+        Self { errors, lines: None }
+    }
+
+    /// Report positions as line and column of the indexed input
+    pub fn with_lines(mut self, lines: LineIndex) -> Self {
+        self.lines = Some(lines);
+        self
+    }
+
+    /// Add an error, keeping position order
+    pub fn push(&mut self, error: ParseError) {
+        let at = self.errors.partition_point(|e| e.position <= error.position);
+        self.errors.insert(at, error);
+    }
+
+    /// The earliest error
+    #[inline]
+    pub fn first(&self) -> Option<&ParseError> {
+        self.errors.first()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, ParseError> {
+        self.errors.iter()
+    }
+
+    /// The errors in position order
+    pub fn into_vec(self) -> Vec<ParseError> {
+        self.errors
+    }
+}
+
+impl From<ParseError> for ParseErrors {
+    fn from(error: ParseError) -> Self {
+        Self::new(vec![error])
+    }
+}
+
+impl From<Vec<ParseError>> for ParseErrors {
+    fn from(errors: Vec<ParseError>) -> Self {
+        Self::new(errors)
+    }
+}
+
+impl<'a> IntoIterator for &'a ParseErrors {
+    type Item = &'a ParseError;
+    type IntoIter = std::slice::Iter<'a, ParseError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match &self.lines {
+                Some(lines) => {
+                    let (line, column) = error.line_column(lines);
+                    write!(f, "{}:{}: {}", line, column, error.message)?;
+                }
+                None => write!(f, "{}", error)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseErrors {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_in_position_order() {
+        let input = "{\n  a: 1\n  b: 2,\n}";
+        let mut errors = ParseErrors::new(vec![
+            ParseError::new("Trailing comma", 15),
+            ParseError::new("Unquoted key", 4),
+        ]);
+        errors.push(ParseError::new("Missing comma", 11));
+
+        assert_eq!(errors.first().map(|e| e.position), Some(4));
+        assert_eq!(
+            errors.to_string(),
+            "Parse error at position 4: Unquoted key\n\
+             Parse error at position 11: Missing comma\n\
+             Parse error at position 15: Trailing comma"
+        );
+        assert_eq!(
+            errors.with_lines(LineIndex::new(input)).to_string(),
+            "2:3: Unquoted key\n3:3: Missing comma\n3:7: Trailing comma"
+        );
+    }
+}
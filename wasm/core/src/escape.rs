@@ -0,0 +1,126 @@
+//! JSON string escaping and unescaping
+//!
+//! Both functions work on string contents without the surrounding quotes.
+
+use crate::ParseError;
+
+/// Escape text for use inside a JSON string literal
+///
+/// Quotes, backslashes and all control characters are escaped; everything
+/// else (including non-ASCII text) is kept as-is.
+pub fn escape_json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    for ch in value.chars() {
+        push_escaped_char(&mut out, ch);
+    }
+    out
+}
+
+/// Append one character to `out`, escaped as needed inside a JSON string
+#[inline]
+pub fn push_escaped_char(out: &mut String, ch: char) {
+    match ch {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\u{08}' => out.push_str("\\b"),
+        '\u{0C}' => out.push_str("\\f"),
+        c if c < '\u{20}' => out.push_str(&format!("\\u{:04x}", c as u32)),
+        c => out.push(c),
+    }
+}
+
+/// Decode the escape sequences in JSON string contents
+///
+/// Handles the standard escapes and `\uXXXX` (including surrogate pairs;
+/// unpaired surrogates become U+FFFD). Any other escaped character decodes
+/// to itself, so lenient forms such as `\'` are accepted.
+pub fn unescape_json_string(value: &str) -> Result<String, ParseError> {
+    if !value.contains('\\') {
+        return Ok(value.to_string());
+    }
+
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.char_indices();
+
+    while let Some((pos, ch)) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some((_, 'n')) => out.push('\n'),
+            Some((_, 'r')) => out.push('\r'),
+            Some((_, 't')) => out.push('\t'),
+            Some((_, 'b')) => out.push('\u{08}'),
+            Some((_, 'f')) => out.push('\u{0C}'),
+            Some((_, 'u')) => {
+                let high = read_hex4(value, pos + 2)?;
+                chars.nth(3);
+                let code = if (0xD800..0xDC00).contains(&high)
+                    && value[pos + 6..].starts_with("\\u")
+                {
+                    match read_hex4(value, pos + 8) {
+                        Ok(low) if (0xDC00..0xE000).contains(&low) => {
+                            chars.nth(5);
+                            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                        }
+                        _ => high,
+                    }
+                } else {
+                    high
+                };
+                out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            }
+            Some((_, other)) => out.push(other),
+            None => return Err(ParseError::new("Unterminated escape sequence", pos)),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Read the four hex digits of a `\u` escape starting at `start`
+fn read_hex4(value: &str, start: usize) -> Result<u32, ParseError> {
+    value
+        .get(start..start + 4)
+        .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+        .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+        .ok_or_else(|| ParseError::new("Invalid unicode escape", start.saturating_sub(2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_round_trip() {
+        let samples = [
+            "plain",
+            "line1\nline2\r\n\ttab",
+            "say \"hi\"",
+            "back\\slash",
+            "ctrl\u{01}\u{08}\u{0C}",
+            "unicode é 😀",
+        ];
+        for sample in samples {
+            let escaped = escape_json_string(sample);
+            assert_eq!(unescape_json_string(&escaped).unwrap(), sample);
+            let via_serde: String = serde_json::from_str(&format!("\"{}\"", escaped)).unwrap();
+            assert_eq!(via_serde, sample);
+        }
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape_json_string(r"\u0041\u00e9").unwrap(), "Aé");
+        assert_eq!(unescape_json_string(r"\ud83d\ude00").unwrap(), "😀");
+        assert_eq!(unescape_json_string(r"\ud83d x").unwrap(), "\u{FFFD} x");
+        assert_eq!(unescape_json_string(r"it\'s \/").unwrap(), "it's /");
+        assert!(unescape_json_string(r"\u12").is_err());
+        assert!(unescape_json_string("end\\").is_err());
+    }
+}
@@ -0,0 +1,309 @@
+//! Cheap input format detection
+//!
+//! Heuristics are applied in this order:
+//! 1. Empty (or whitespace-only) input is `Unknown`
+//! 2. Input starting with `{` or `[` is JSON, unless every non-blank line
+//!    is a complete `{...}` / `[...]` record and there are at least two of them,
+//!    in which case it is NDJSON. Input starting with `"` is CSV when the first
+//!    line is a closed quoted field followed by a delimiter that is consistent
+//!    across the sampled lines, and JSON otherwise
+//! 3. Input whose sampled lines all contain the same non-zero number of a
+//!    candidate delimiter (outside quotes) is CSV
+//! 4. Anything else is `Unknown`
+
+use serde::Serialize;
+
+/// Number of lines sampled for delimiter detection
+const SAMPLE_LINES: usize = 20;
+
+/// Candidate CSV delimiters, in order of preference
+pub const CANDIDATE_DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+
+/// Candidate CSV quote characters, in order of preference
+const CANDIDATE_QUOTES: [char; 2] = ['"', '\''];
+
+/// Inferred CSV dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvDialect {
+    /// Field delimiter (`,` when ambiguous)
+    pub delimiter: char,
+    /// Quote character (`"` when no field is quoted)
+    pub quote: char,
+    /// Whether any sampled field is wrapped in the quote character
+    pub quoted_fields: bool,
+    /// `"\r\n"` or `"\n"`
+    pub line_terminator: &'static str,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            quoted_fields: false,
+            line_terminator: "\n",
+        }
+    }
+}
+
+/// Detected input format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Ndjson,
+    Csv,
+    Unknown,
+}
+
+impl Format {
+    /// Lowercase name of the format
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Ndjson => "ndjson",
+            Format::Csv => "csv",
+            Format::Unknown => "unknown",
+        }
+    }
+}
+
+/// Guess the format of `input` using cheap heuristics
+pub fn detect_format(input: &str) -> Format {
+    let trimmed = input.trim_start_matches(|c: char| c.is_whitespace() || c == '\u{FEFF}');
+
+    let first = match trimmed.chars().next() {
+        Some(c) => c,
+        None => return Format::Unknown,
+    };
+
+    if matches!(first, '{' | '[') {
+        if is_ndjson(trimmed) {
+            return Format::Ndjson;
+        }
+        return Format::Json;
+    }
+    if first == '"' {
+        return if starts_with_quoted_field(trimmed) { Format::Csv } else { Format::Json };
+    }
+
+    if detect_delimiter(trimmed).is_some() {
+        return Format::Csv;
+    }
+
+    Format::Unknown
+}
+
+/// Whether the first line opens with a closed quoted field and a delimiter
+///
+/// A JSON document can't continue a top-level string with a delimiter, so
+/// this decides CSV as long as the delimiter also splits the other sampled
+/// lines consistently (a single line is enough on its own).
+fn starts_with_quoted_field(input: &str) -> bool {
+    let line = input.lines().next().unwrap_or("");
+    let mut chars = line.char_indices().skip(1).peekable();
+    let mut close = None;
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            // A doubled quote is a literal quote inside a CSV field
+            '"' if chars.next_if(|&(_, next)| next == '"').is_some() => {}
+            '"' => {
+                close = Some(pos + 1);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let after = close.and_then(|close| line[close..].trim_start_matches(' ').chars().next());
+    let Some(after) = after else {
+        return false;
+    };
+    if !CANDIDATE_DELIMITERS.contains(&after) {
+        return false;
+    }
+    let lines = sample_lines(input);
+    lines.len() == 1 || consistent_delimiter(&lines, '"') == Some(after)
+}
+
+/// Find a delimiter that occurs the same non-zero number of times on every sampled line
+///
+/// Delimiters inside double-quoted regions are ignored. Returns `None` when no
+/// candidate is consistent across at least two lines.
+pub fn detect_delimiter(input: &str) -> Option<char> {
+    consistent_delimiter(&sample_lines(input), '"')
+}
+
+/// Infer delimiter, quote character and line terminator from the first lines
+///
+/// The quote is the candidate that most often wraps a whole field (from a
+/// line start or delimiter up to the next delimiter or line end). Ambiguous
+/// inputs fall back to `CsvDialect::default()` values.
+pub fn detect_dialect(input: &str) -> CsvDialect {
+    let defaults = CsvDialect::default();
+    let lines = sample_lines(input);
+
+    let (quote, wrapped) = CANDIDATE_QUOTES
+        .iter()
+        .map(|&quote| (quote, count_wrapped_fields(&lines, quote)))
+        .fold((defaults.quote, 0), |best, candidate| {
+            if candidate.1 > best.1 { candidate } else { best }
+        });
+
+    let first_break = input.find('\n');
+    let line_terminator = match first_break {
+        Some(pos) if pos > 0 && input.as_bytes()[pos - 1] == b'\r' => "\r\n",
+        _ => defaults.line_terminator,
+    };
+
+    CsvDialect {
+        delimiter: consistent_delimiter(&lines, quote).unwrap_or(defaults.delimiter),
+        quote,
+        quoted_fields: wrapped > 0,
+        line_terminator,
+    }
+}
+
+/// Non-blank lines sampled for detection
+fn sample_lines(input: &str) -> Vec<&str> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(SAMPLE_LINES)
+        .collect()
+}
+
+/// First candidate delimiter with the same non-zero unquoted count on every line
+fn consistent_delimiter(lines: &[&str], quote: char) -> Option<char> {
+    if lines.len() < 2 {
+        return None;
+    }
+
+    CANDIDATE_DELIMITERS.iter().copied().find(|&delimiter| {
+        let expected = count_unquoted(lines[0], delimiter, quote);
+        expected > 0
+            && lines[1..]
+                .iter()
+                .all(|line| count_unquoted(line, delimiter, quote) == expected)
+    })
+}
+
+/// Count fields fully wrapped in `quote`, bounded by line edges or candidate delimiters
+fn count_wrapped_fields(lines: &[&str], quote: char) -> usize {
+    let is_boundary = |c: Option<char>| c.is_none_or(|c| CANDIDATE_DELIMITERS.contains(&c));
+    let mut count = 0;
+
+    for line in lines {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let opens = chars[i] == quote && is_boundary(i.checked_sub(1).map(|p| chars[p]));
+            let close = if opens {
+                chars[i + 1..].iter().position(|&c| c == quote).map(|offset| i + 1 + offset)
+            } else {
+                None
+            };
+            match close {
+                Some(end) if is_boundary(chars.get(end + 1).copied()) => {
+                    count += 1;
+                    i = end + 1;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
+    count
+}
+
+/// Count occurrences of `delimiter` outside `quote`-delimited regions
+fn count_unquoted(line: &str, delimiter: char, quote: char) -> usize {
+    let mut in_quotes = false;
+    let mut count = 0;
+
+    for ch in line.chars() {
+        if ch == quote {
+            in_quotes = !in_quotes;
+        } else if ch == delimiter && !in_quotes {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// Check whether every non-blank line is a standalone JSON object or array
+fn is_ndjson(input: &str) -> bool {
+    let mut records = 0;
+
+    for line in input.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let complete = (line.starts_with('{') && line.ends_with('}'))
+            || (line.starts_with('[') && line.ends_with(']'));
+        if !complete {
+            return false;
+        }
+        records += 1;
+    }
+
+    records >= 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_json() {
+        assert_eq!(detect_format("  {\"a\": 1}"), Format::Json);
+        assert_eq!(detect_format("[1,\n 2,\n 3]"), Format::Json);
+        assert_eq!(detect_format("{\n  a: 1,\n  b: 2\n}"), Format::Json);
+        assert_eq!(detect_format("\"just a string\""), Format::Json);
+        assert_eq!(detect_format("\"a,b\"\n"), Format::Json);
+    }
+
+    #[test]
+    fn test_detect_ndjson() {
+        assert_eq!(detect_format("{\"a\":1}\n{\"a\":2}\n\n{\"a\":3}\n"), Format::Ndjson);
+    }
+
+    #[test]
+    fn test_detect_csv() {
+        assert_eq!(detect_format("name,age\nAlice,30\nBob,25"), Format::Csv);
+        assert_eq!(detect_format("a;b\n\"x;y\";2"), Format::Csv);
+        assert_eq!(detect_delimiter("a\tb\n1\t2"), Some('\t'));
+        // A quoted header is not a JSON string
+        assert_eq!(detect_format("\"name\",\"age\"\n\"Alice\",30\n"), Format::Csv);
+        assert_eq!(detect_format("\"say \"\"hi\"\"\";b\n1;2"), Format::Csv);
+        assert_eq!(detect_format("\"name\",\"age\""), Format::Csv);
+    }
+
+    #[test]
+    fn test_detect_dialect() {
+        let dialect = detect_dialect("'name';'note'\r\n'Alice';'a;b'\r\n'Bob';'c'\r\n");
+        assert_eq!(
+            dialect,
+            CsvDialect {
+                delimiter: ';',
+                quote: '\'',
+                quoted_fields: true,
+                line_terminator: "\r\n",
+            }
+        );
+
+        assert_eq!(detect_dialect("hello"), CsvDialect::default());
+    }
+
+    #[test]
+    fn test_detect_unknown() {
+        assert_eq!(detect_format(""), Format::Unknown);
+        assert_eq!(detect_format("hello world"), Format::Unknown);
+        assert_eq!(detect_format("a,b\nc"), Format::Unknown);
+    }
+}
@@ -5,6 +5,30 @@
 
 use std::fmt;
 
+mod assemble;
+mod case;
+mod errors;
+mod escape;
+mod format;
+mod line_index;
+mod scalar;
+mod simd;
+mod span;
+
+pub use assemble::{assemble_value, MAX_ASSEMBLE_DEPTH};
+pub use case::{split_words, to_camel_case, to_snake_case, to_title_case};
+pub use errors::ParseErrors;
+pub use escape::{escape_json_string, push_escaped_char, unescape_json_string};
+pub use format::{
+    detect_delimiter, detect_dialect, detect_format, CsvDialect, Format, CANDIDATE_DELIMITERS,
+};
+pub use line_index::LineIndex;
+pub use scalar::{classify_scalar, is_json_number, ScalarKind};
+#[cfg(target_arch = "wasm32")]
+pub use simd::simd_find_bytes;
+pub use simd::{find_bytes_scalar, ByteSet};
+pub use span::Span;
+
 /// Token type for parsers
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TokenType {
@@ -41,6 +65,12 @@ impl Token {
             end,
         }
     }
+
+    /// Source range covered by the token
+    #[inline]
+    pub fn span(&self) -> Span {
+        Span::new(self.start, self.end)
+    }
 }
 
 /// Parse error with position information
@@ -57,6 +87,11 @@ impl ParseError {
             position,
         }
     }
+
+    /// 1-based line and column of the error
+    pub fn line_column(&self, index: &LineIndex) -> (usize, usize) {
+        index.locate(self.position)
+    }
 }
 
 impl fmt::Display for ParseError {
@@ -91,10 +126,101 @@ pub fn is_identifier_char(c: char) -> bool {
     c.is_ascii_alphanumeric() || c == '_' || c == '$'
 }
 
+/// Set of comment syntaxes recognized when skipping whitespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentStyles(u8);
+
+impl CommentStyles {
+    /// No comments are recognized
+    pub const NONE: Self = Self(0);
+    /// `// line comments`
+    pub const DOUBLE_SLASH: Self = Self(1);
+    /// `/* block comments */`
+    pub const SLASH_STAR: Self = Self(1 << 1);
+    /// `# line comments`
+    pub const HASH: Self = Self(1 << 2);
+    /// All supported comment styles
+    pub const ALL: Self = Self(0b111);
+
+    /// Whether every style in `other` is enabled
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Combine two style sets
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl Default for CommentStyles {
+    /// JavaScript-style `//` and `/* */` comments
+    fn default() -> Self {
+        Self::DOUBLE_SLASH.union(Self::SLASH_STAR)
+    }
+}
+
+impl std::ops::BitOr for CommentStyles {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CommentStyles {
+    /// Deserialize from a list of style names: `"doubleSlash"`, `"slashStar"`, `"hash"`
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let names = Vec::<String>::deserialize(deserializer)?;
+        names.iter().try_fold(Self::NONE, |styles, name| {
+            let style = match name.as_str() {
+                "doubleSlash" => Self::DOUBLE_SLASH,
+                "slashStar" => Self::SLASH_STAR,
+                "hash" => Self::HASH,
+                other => {
+                    return Err(serde::de::Error::unknown_variant(
+                        other,
+                        &["doubleSlash", "slashStar", "hash"],
+                    ))
+                }
+            };
+            Ok(styles | style)
+        })
+    }
+}
+
+/// Check whether a comment of one of the given styles starts at `pos`
+#[inline]
+pub fn comment_starts_at(bytes: &[u8], pos: usize, end: usize, styles: CommentStyles) -> bool {
+    match bytes.get(pos) {
+        Some(b'#') if pos < end => styles.contains(CommentStyles::HASH),
+        Some(b'/') if pos + 1 < end => match bytes[pos + 1] {
+            b'/' => styles.contains(CommentStyles::DOUBLE_SLASH),
+            b'*' => styles.contains(CommentStyles::SLASH_STAR),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 /// Skip whitespace and comments
-pub fn skip_whitespace_and_comments(input: &str, mut pos: usize) -> usize {
-    let bytes = input.as_bytes();
-    let len = bytes.len();
+pub fn skip_whitespace_and_comments(input: &str, pos: usize) -> usize {
+    skip_whitespace_and_comments_with(input.as_bytes(), pos, input.len(), CommentStyles::default())
+}
+
+/// UTF-8 encoding of the byte order mark U+FEFF
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Skip whitespace and the given comment styles within `pos..end`
+pub fn skip_whitespace_and_comments_with(
+    bytes: &[u8],
+    mut pos: usize,
+    end: usize,
+    styles: CommentStyles,
+) -> usize {
+    let len = end;
 
     while pos < len {
         let c = bytes[pos] as char;
@@ -105,8 +231,18 @@ pub fn skip_whitespace_and_comments(input: &str, mut pos: usize) -> usize {
             continue;
         }
 
+        // Skip byte order marks (U+FEFF), e.g. from Windows editors
+        if bytes[pos..len].starts_with(BOM) {
+            pos += BOM.len();
+            continue;
+        }
+
         // Skip single-line comments
-        if c == '/' && pos + 1 < len && bytes[pos + 1] == b'/' {
+        if c == '/'
+            && styles.contains(CommentStyles::DOUBLE_SLASH)
+            && pos + 1 < len
+            && bytes[pos + 1] == b'/'
+        {
             pos += 2;
             while pos < len && bytes[pos] != b'\n' {
                 pos += 1;
@@ -114,8 +250,21 @@ pub fn skip_whitespace_and_comments(input: &str, mut pos: usize) -> usize {
             continue;
         }
 
+        // Skip hash comments
+        if c == '#' && styles.contains(CommentStyles::HASH) {
+            pos += 1;
+            while pos < len && bytes[pos] != b'\n' {
+                pos += 1;
+            }
+            continue;
+        }
+
         // Skip multi-line comments
-        if c == '/' && pos + 1 < len && bytes[pos + 1] == b'*' {
+        if c == '/'
+            && styles.contains(CommentStyles::SLASH_STAR)
+            && pos + 1 < len
+            && bytes[pos + 1] == b'*'
+        {
             pos += 2;
             while pos + 1 < len {
                 if bytes[pos] == b'*' && bytes[pos + 1] == b'/' {
@@ -158,4 +307,17 @@ mod tests {
         assert_eq!(skip_whitespace_and_comments("// comment\nhello", 0), 11);
         assert_eq!(skip_whitespace_and_comments("/* comment */hello", 0), 13);
     }
+
+    #[test]
+    fn test_skip_comment_styles() {
+        let input = b"# hash\n// slash\nx";
+        let hash_only = CommentStyles::HASH;
+        assert_eq!(skip_whitespace_and_comments_with(input, 0, input.len(), hash_only), 7);
+        assert_eq!(skip_whitespace_and_comments_with(input, 0, input.len(), CommentStyles::ALL), 16);
+        assert_eq!(skip_whitespace_and_comments_with(input, 0, input.len(), CommentStyles::default()), 0);
+
+        assert!(comment_starts_at(input, 0, input.len(), CommentStyles::HASH));
+        assert!(!comment_starts_at(input, 7, input.len(), CommentStyles::HASH));
+        assert!(comment_starts_at(input, 7, input.len(), CommentStyles::default()));
+    }
 }
@@ -0,0 +1,78 @@
+//! Byte offset to line/column mapping
+
+/// Precomputed line starts for O(log n) position lookups
+///
+/// Build once per input, then `locate` any number of positions without
+/// rescanning from the start of the document.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// Byte offset at which each line starts
+    line_starts: Vec<usize>,
+    len: usize,
+}
+
+impl LineIndex {
+    /// Index the line starts of `input` (`\n`, `\r\n` and lone `\r` end a line)
+    pub fn new(input: &str) -> Self {
+        let bytes = input.as_bytes();
+        let mut line_starts = vec![0];
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            match byte {
+                b'\n' => line_starts.push(i + 1),
+                b'\r' if bytes.get(i + 1) != Some(&b'\n') => line_starts.push(i + 1),
+                _ => {}
+            }
+        }
+
+        LineIndex {
+            line_starts,
+            len: input.len(),
+        }
+    }
+
+    /// Number of lines in the input
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Map a byte offset to a 1-based `(line, column)` pair
+    ///
+    /// Columns count bytes from the start of the line. Offsets past the end
+    /// of the input are clamped to the end.
+    pub fn locate(&self, pos: usize) -> (usize, usize) {
+        let pos = pos.min(self.len);
+        let line = match self.line_starts.binary_search(&pos) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        (line + 1, pos - self.line_starts[line] + 1)
+    }
+
+    /// Byte offset at which a 1-based line starts
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        line.checked_sub(1).and_then(|i| self.line_starts.get(i).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate() {
+        let input = "{\n  \"a\": 1,\r\n  \"b\": 2\n}";
+        let index = LineIndex::new(input);
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.locate(0), (1, 1));
+        assert_eq!(index.locate(1), (1, 2));
+        assert_eq!(index.locate(2), (2, 1));
+        assert_eq!(index.locate(4), (2, 3));
+        assert_eq!(index.locate(input.find("\"b\"").unwrap()), (3, 3));
+        assert_eq!(index.locate(input.len() - 1), (4, 1));
+        assert_eq!(index.locate(999), (4, 2));
+        assert_eq!(index.line_start(3), Some(13));
+    }
+}
@@ -0,0 +1,123 @@
+//! Classification of scalar text shared by the JSON and CSV crates
+//!
+//! Both crates decide whether text such as a CSV cell or a JSON string reads
+//! as a number, boolean or null. Keeping that decision here means `"007"` or
+//! `"+1"` is classified the same way everywhere.
+
+/// What a piece of text denotes as a JSON scalar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarKind {
+    /// A JSON integer literal that fits in `i64`
+    Integer,
+    /// Any other JSON number literal with a finite `f64` value
+    Float,
+    /// `true` or `false`
+    Bool,
+    /// `null`
+    Null,
+    /// Anything else, including number-like text JSON rejects (`007`,
+    /// `+1`, `.5`, `1.`, `NaN`) and numbers that overflow `f64`
+    String,
+}
+
+/// Classify `s` by the JSON scalar it spells exactly
+///
+/// Surrounding whitespace is not trimmed, so `" 1"` is a string.
+pub fn classify_scalar(s: &str) -> ScalarKind {
+    match s {
+        "true" | "false" => return ScalarKind::Bool,
+        "null" => return ScalarKind::Null,
+        _ => {}
+    }
+    if !is_json_number(s) {
+        return ScalarKind::String;
+    }
+    if !s.contains(['.', 'e', 'E']) && s.parse::<i64>().is_ok() {
+        return ScalarKind::Integer;
+    }
+    match s.parse::<f64>() {
+        Ok(n) if n.is_finite() => ScalarKind::Float,
+        _ => ScalarKind::String,
+    }
+}
+
+/// Whether `value` is exactly a JSON number literal (no sign prefix `+`,
+/// leading zeros, bare decimal points or surrounding whitespace)
+pub fn is_json_number(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    let mut pos = usize::from(bytes.first() == Some(&b'-'));
+    let digits = |pos: &mut usize| {
+        let start = *pos;
+        while bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+            *pos += 1;
+        }
+        *pos - start
+    };
+
+    match digits(&mut pos) {
+        0 => return false,
+        n if n > 1 && bytes[pos - n] == b'0' => return false,
+        _ => {}
+    }
+    if bytes.get(pos) == Some(&b'.') {
+        pos += 1;
+        if digits(&mut pos) == 0 {
+            return false;
+        }
+    }
+    if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+        pos += 1;
+        if matches!(bytes.get(pos), Some(b'+' | b'-')) {
+            pos += 1;
+        }
+        if digits(&mut pos) == 0 {
+            return false;
+        }
+    }
+    pos == bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_scalar() {
+        let cases = [
+            ("0", ScalarKind::Integer),
+            ("-0", ScalarKind::Integer),
+            ("42", ScalarKind::Integer),
+            ("-9223372036854775808", ScalarKind::Integer),
+            ("9223372036854775808", ScalarKind::Float),
+            ("1.0", ScalarKind::Float),
+            ("-2.5e-3", ScalarKind::Float),
+            ("1E5", ScalarKind::Float),
+            ("1e400", ScalarKind::String),
+            ("007", ScalarKind::String),
+            ("+1", ScalarKind::String),
+            (".5", ScalarKind::String),
+            ("1.", ScalarKind::String),
+            ("0x10", ScalarKind::String),
+            ("NaN", ScalarKind::String),
+            ("inf", ScalarKind::String),
+            (" 1", ScalarKind::String),
+            ("", ScalarKind::String),
+            ("true", ScalarKind::Bool),
+            ("False", ScalarKind::String),
+            ("null", ScalarKind::Null),
+        ];
+        for (text, kind) in cases {
+            assert_eq!(classify_scalar(text), kind, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_is_json_number() {
+        for valid in ["0", "-0", "42", "-1.5", "1e10", "2.5E-3", "0.0"] {
+            assert!(is_json_number(valid), "{}", valid);
+        }
+        for invalid in ["", "-", "01", "+1", ".5", "1.", "1e", "42abc", " 42 ", "0x10", "NaN"] {
+            assert!(!is_json_number(invalid), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Byte-set classification
+//!
+//! Shared primitive behind the SIMD scanners: given a set of needle bytes,
+//! produce a bitmask marking which bytes of a 16-byte chunk belong to the
+//! set. On wasm32 the comparison runs on a `v128` (one `i8x16_eq` per
+//! needle); every target gets a scalar version with identical results.
+
+#[cfg(target_arch = "wasm32")]
+use std::arch::wasm32::*;
+
+/// Bitmask of the bytes in `chunk` equal to any of `needles`
+///
+/// Bit `i` is set when byte `i` of the chunk matches.
+#[cfg(target_arch = "wasm32")]
+#[inline(always)]
+pub fn simd_find_bytes(chunk: v128, needles: &[u8]) -> u16 {
+    let mut matched = i8x16_splat(0);
+    for &needle in needles {
+        matched = v128_or(matched, i8x16_eq(chunk, u8x16_splat(needle)));
+    }
+    i8x16_bitmask(matched)
+}
+
+/// Scalar equivalent of `simd_find_bytes` for up to 16 bytes
+#[inline]
+pub fn find_bytes_scalar(chunk: &[u8], needles: &[u8]) -> u16 {
+    chunk
+        .iter()
+        .take(16)
+        .enumerate()
+        .filter(|(_, byte)| needles.contains(byte))
+        .fold(0, |mask, (bit, _)| mask | (1 << bit))
+}
+
+/// Needle set with its comparison vectors precomputed
+///
+/// Build once per scan and call `mask` per chunk, so the splats aren't
+/// recreated for every 16 bytes.
+#[derive(Debug, Clone)]
+pub struct ByteSet {
+    table: [bool; 256],
+    #[cfg(target_arch = "wasm32")]
+    splats: Vec<v128>,
+}
+
+impl ByteSet {
+    pub fn new(needles: &[u8]) -> Self {
+        let mut table = [false; 256];
+        for &needle in needles {
+            table[needle as usize] = true;
+        }
+        ByteSet {
+            table,
+            #[cfg(target_arch = "wasm32")]
+            splats: needles.iter().map(|&needle| u8x16_splat(needle)).collect(),
+        }
+    }
+
+    /// Check whether a single byte is in the set
+    #[inline(always)]
+    pub fn contains(&self, byte: u8) -> bool {
+        self.table[byte as usize]
+    }
+
+    /// Bitmask of the chunk bytes in the set
+    #[cfg(target_arch = "wasm32")]
+    #[inline(always)]
+    pub fn mask(&self, chunk: v128) -> u16 {
+        let mut matched = i8x16_splat(0);
+        for &splat in &self.splats {
+            matched = v128_or(matched, i8x16_eq(chunk, splat));
+        }
+        i8x16_bitmask(matched)
+    }
+
+    /// Bitmask of the bytes in the set for up to 16 bytes of `input` at `start`
+    ///
+    /// Uses SIMD on wasm32 when a full chunk is available.
+    #[inline]
+    pub fn mask_at(&self, input: &[u8], start: usize) -> u16 {
+        #[cfg(target_arch = "wasm32")]
+        if start + 16 <= input.len() {
+            // SAFETY: the 16 bytes at `start` are in bounds; v128_load allows unaligned reads
+            let chunk = unsafe { v128_load(input.as_ptr().add(start) as *const v128) };
+            return self.mask(chunk);
+        }
+        self.mask_scalar(&input[start..(start + 16).min(input.len())])
+    }
+
+    /// Scalar bitmask for up to 16 bytes
+    #[inline]
+    pub fn mask_scalar(&self, chunk: &[u8]) -> u16 {
+        chunk
+            .iter()
+            .take(16)
+            .enumerate()
+            .filter(|(_, &byte)| self.contains(byte))
+            .fold(0, |mask, (bit, _)| mask | (1 << bit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_needles_match_scalar() {
+        let needles = b",\"\n\r\t";
+        let set = ByteSet::new(needles);
+        let input = b"id,name\r\n1,\"a, b\"\n2,\tc\n3,d,e,f,g,h,i,j";
+
+        for start in 0..input.len() {
+            let chunk = &input[start..(start + 16).min(input.len())];
+            let expected = chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| needles.contains(b))
+                .fold(0u16, |mask, (bit, _)| mask | (1 << bit));
+            assert_eq!(find_bytes_scalar(chunk, needles), expected);
+            assert_eq!(set.mask_scalar(chunk), expected);
+            assert_eq!(set.mask_at(input, start), expected);
+        }
+
+        assert_eq!(set.mask_at(b"a,b\"c", 0), 0b01010);
+        assert!(set.contains(b'\r') && !set.contains(b'a'));
+    }
+}
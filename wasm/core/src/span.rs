@@ -0,0 +1,60 @@
+//! Byte ranges into source text
+
+/// Half-open byte range `start..end` into the source input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Length in bytes
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The source text covered by this span
+    ///
+    /// Panics if the span is out of bounds or not on character boundaries,
+    /// like slicing with `&input[start..end]`.
+    #[inline]
+    pub fn slice<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.start..self.end]
+    }
+
+    /// Smallest span covering both spans
+    pub fn merge(&self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Token, TokenType};
+
+    #[test]
+    fn test_token_span_slice() {
+        let input = r#"{"key": 42}"#;
+        let token = Token::new(TokenType::String, "key".to_string(), 1, 6);
+        assert_eq!(token.span().slice(input), r#""key""#);
+        assert_eq!(token.span().len(), 5);
+    }
+
+    #[test]
+    fn test_merge() {
+        let merged = Span::new(4, 6).merge(Span::new(1, 3));
+        assert_eq!(merged, Span::new(1, 6));
+        assert!(Span::new(2, 2).is_empty());
+    }
+}
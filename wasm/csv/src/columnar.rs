@@ -0,0 +1,158 @@
+//! Columnar CSV output
+//!
+//! Instead of one object per row, the header and inferred column types are
+//! emitted once alongside positional rows:
+//! `{"columns": [...], "types": [...], "rows": [[...], ...]}`. This is more
+//! compact for wide data and maps directly onto DataFrame-style loaders.
+//!
+//! `parse_csv_numeric` is the native analytics path for all-numeric files:
+//! it skips `Value` entirely and returns column-major `f64` vectors.
+
+use molt_core::{classify_scalar, ScalarKind};
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::schema::{infer_column_type, typed_value};
+use crate::{split_records, ParseOptions};
+
+/// Parse CSV into columns, inferred types and positional rows
+///
+/// The first row is always the header. Each column's type is the narrowest
+/// of integer, number and boolean that all its non-empty cells satisfy,
+/// otherwise string; empty cells in typed columns become `null`.
+///
+/// # Arguments
+/// * `input` - CSV string to parse
+/// * `delimiter` - Field delimiter (default: ',')
+#[wasm_bindgen]
+pub fn parse_csv_columnar(input: &str, delimiter: Option<char>) -> Result<String, JsValue> {
+    let defaults = ParseOptions::default();
+    let options = ParseOptions {
+        delimiter: delimiter.unwrap_or(defaults.delimiter),
+        ..defaults
+    };
+
+    parse_csv_columnar_with(input, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Parse CSV into columnar JSON with explicit options
+pub fn parse_csv_columnar_with(input: &str, options: &ParseOptions) -> Result<String, String> {
+    let records = split_records(input, options);
+    let Some((header, data_rows)) = records.split_first() else {
+        return Ok(json!({ "columns": [], "types": [], "rows": [] }).to_string());
+    };
+
+    let types: Vec<_> = (0..header.len())
+        .map(|col| infer_column_type(data_rows.iter().map(move |row| cell(row, col))))
+        .collect();
+
+    let rows: Vec<Value> = data_rows
+        .iter()
+        .map(|row| {
+            let values: Vec<Value> = types
+                .iter()
+                .enumerate()
+                .map(|(col, &column_type)| {
+                    typed_value(cell(row, col), column_type).unwrap_or(Value::Null)
+                })
+                .collect();
+            Value::Array(values)
+        })
+        .collect();
+
+    let result = json!({ "columns": header, "types": types, "rows": rows });
+    serde_json::to_string(&result).map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Parse an all-numeric CSV into its header and column-major `f64` data
+///
+/// `columns[c][r]` is the value of column `c` in data row `r`. Cells are
+/// numbers by the same rules as typed columns: JSON number syntax, so `007`,
+/// `+1`, `NaN` and `inf` don't count. A cell that isn't a number (including
+/// an empty or missing cell) is an error naming its column, unless
+/// `fill_nan` is set, in which case it becomes `NaN`.
+pub fn parse_csv_numeric(
+    input: &str,
+    delimiter: char,
+    fill_nan: bool,
+) -> Result<(Vec<String>, Vec<Vec<f64>>), String> {
+    let options = ParseOptions { delimiter, ..ParseOptions::default() };
+    let mut records = split_records(input, &options).into_iter();
+    let Some(header) = records.next() else {
+        return Ok((Vec::new(), Vec::new()));
+    };
+
+    let mut columns: Vec<Vec<f64>> = vec![Vec::new(); header.len()];
+    for (row_index, row) in records.enumerate() {
+        for (col, column) in columns.iter_mut().enumerate() {
+            let raw = cell(&row, col);
+            let value = match numeric_cell(raw) {
+                Some(value) => value,
+                None if fill_nan => f64::NAN,
+                None => {
+                    return Err(format!(
+                        "Non-numeric value {:?} in column '{}' (row {})",
+                        raw,
+                        header[col],
+                        row_index + 1
+                    ));
+                }
+            };
+            column.push(value);
+        }
+    }
+
+    Ok((header, columns))
+}
+
+/// Value of a cell that is a JSON number, ignoring surrounding whitespace
+fn numeric_cell(raw: &str) -> Option<f64> {
+    let trimmed = raw.trim();
+    match classify_scalar(trimmed) {
+        ScalarKind::Integer | ScalarKind::Float => trimmed.parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+/// Cell text at `col`, or empty for short rows
+fn cell(row: &[String], col: usize) -> &str {
+    row.get(col).map(String::as_str).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric() {
+        let input = "t,x,y\n0,1.5,-2\n1,2.5,-3\n2,3.5,-4\n";
+        let (header, columns) = parse_csv_numeric(input, ',', false).unwrap();
+        assert_eq!(header, ["t", "x", "y"]);
+        assert_eq!(
+            columns,
+            vec![vec![0.0, 1.0, 2.0], vec![1.5, 2.5, 3.5], vec![-2.0, -3.0, -4.0]]
+        );
+
+        let err = parse_csv_numeric("a;b\n1;x", ';', false).unwrap_err();
+        assert_eq!(err, "Non-numeric value \"x\" in column 'b' (row 1)");
+        let (_, columns) = parse_csv_numeric("a;b\n1;x", ';', true).unwrap();
+        assert!(columns[1][0].is_nan());
+
+        for cell in ["007", "+1", "NaN", "inf", ".5"] {
+            let input = format!("a\n{}", cell);
+            assert!(parse_csv_numeric(&input, ',', false).is_err(), "{}", cell);
+        }
+        let (_, columns) = parse_csv_numeric("a\n 1e3 \n-0.5", ',', false).unwrap();
+        assert_eq!(columns, vec![vec![1000.0, -0.5]]);
+    }
+
+    #[test]
+    fn test_columnar() {
+        let input = "name,age\nAlice,30\nBob,\n";
+        let result = parse_csv_columnar_with(input, &ParseOptions::default()).unwrap();
+        assert_eq!(
+            result,
+            r#"{"columns":["name","age"],"types":["string","integer"],"rows":[["Alice",30],["Bob",null]]}"#
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Named option presets for the CSV flavours of common tools
+//!
+//! A preset expands into full parse and stringify option sets. Rust callers
+//! override individual fields with struct update syntax; the options-object
+//! entry points take a `dialect` name and apply the remaining fields on top.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{ParseOptions, StringifyOptions};
+
+/// Tools whose CSV flavour has a preset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Preset {
+    /// Comma, CRLF, byte order mark and quoted ambiguous fields
+    Excel,
+    /// PostgreSQL `COPY` text format: tab, no header, `\N` nulls and
+    /// backslash escapes instead of quoting
+    PostgresCopy,
+    /// Comma, CRLF and quoted ambiguous fields
+    Rfc4180Strict,
+    /// Comma and LF, the plain defaults
+    Unix,
+}
+
+/// Parse and stringify options bundled for one CSV flavour
+#[derive(Debug, Clone)]
+pub struct CsvDialect {
+    pub parse: ParseOptions,
+    pub stringify: StringifyOptions,
+}
+
+impl CsvDialect {
+    /// Full option sets for `preset`
+    pub fn preset(preset: Preset) -> Self {
+        let parse = ParseOptions::default();
+        let stringify = StringifyOptions::default();
+        match preset {
+            Preset::Excel => Self {
+                parse,
+                stringify: StringifyOptions { strict_rfc4180: true, bom: true, ..stringify },
+            },
+            Preset::PostgresCopy => Self {
+                parse: ParseOptions {
+                    delimiter: '\t',
+                    has_header: false,
+                    null_values: vec!["\\N".to_string()],
+                    backslash_escapes: true,
+                    ..parse
+                },
+                stringify: StringifyOptions {
+                    delimiter: '\t',
+                    include_header: false,
+                    null_value: "\\N".to_string(),
+                    backslash_escapes: true,
+                    ..stringify
+                },
+            },
+            Preset::Rfc4180Strict => Self {
+                parse,
+                stringify: StringifyOptions { strict_rfc4180: true, ..stringify },
+            },
+            Preset::Unix => Self { parse, stringify },
+        }
+    }
+}
+
+/// Read an options object, starting from its `dialect` preset if it names one
+///
+/// `select` picks the parse or stringify half of the preset; fields given in
+/// the object override the preset's.
+pub(crate) fn options_from_json<T>(
+    options_json: &str,
+    select: impl FnOnce(CsvDialect) -> T,
+) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let invalid = |e: serde_json::Error| format!("Invalid options: {}", e);
+    let mut fields: Value = serde_json::from_str(options_json).map_err(invalid)?;
+
+    let preset = match fields.as_object_mut().and_then(|fields| fields.remove("dialect")) {
+        Some(preset) => serde_json::from_value::<Preset>(preset).map_err(invalid)?,
+        None => return serde_json::from_value(fields).map_err(invalid),
+    };
+    let mut options = serde_json::to_value(select(CsvDialect::preset(preset))).map_err(invalid)?;
+    if let (Some(options), Value::Object(fields)) = (options.as_object_mut(), fields) {
+        options.extend(fields);
+    }
+    serde_json::from_value(options).map_err(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_csv_with, stringify_csv_with};
+    use serde_json::json;
+
+    #[test]
+    fn test_postgres_copy() {
+        let dialect = CsvDialect::preset(Preset::PostgresCopy);
+        let input = "1\t\\N\tsay \"hi\"\n2\ttab\\there\t\\N";
+        assert_eq!(
+            parse_csv_with(input, &dialect.parse).unwrap(),
+            r#"[[1,null,"say \"hi\""],[2,"tab\there",null]]"#
+        );
+
+        let data = json!([[1, null, "a\tb"], ["back\\slash", "x", null]]);
+        assert_eq!(
+            stringify_csv_with(&data, &dialect.stringify).unwrap(),
+            "1\t\\N\ta\\tb\nback\\\\slash\tx\t\\N"
+        );
+    }
+
+    #[test]
+    fn test_options_override_preset() {
+        let options: ParseOptions =
+            options_from_json(r#"{"dialect": "postgresCopy", "hasHeader": true}"#, |d| d.parse)
+                .unwrap();
+        assert_eq!(options.delimiter, '\t');
+        assert!(options.has_header);
+
+        let options: StringifyOptions =
+            options_from_json(r#"{"dialect": "excel"}"#, |d| d.stringify).unwrap();
+        let csv = stringify_csv_with(&json!([{"a": "007"}]), &options).unwrap();
+        assert_eq!(csv, "\u{feff}a\r\n\"007\"");
+        // The byte order mark doesn't leak into the first header name
+        assert_eq!(parse_csv_with(&csv, &ParseOptions::default()).unwrap(), r#"[{"a":"007"}]"#);
+    }
+}
@@ -0,0 +1,149 @@
+//! Fixed-width column parsing
+//!
+//! Legacy exports sometimes have no delimiter at all: each column occupies a
+//! fixed number of characters. Lines are sliced by the configured widths,
+//! trimmed, and then assembled exactly like delimited CSV records.
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{records_to_json, ParseOptions};
+
+/// Parse fixed-width text to a JSON array
+///
+/// # Arguments
+/// * `input` - Fixed-width text to parse
+/// * `widths` - Width of each column
+/// * `has_header` - Whether first row is header (default: true)
+#[wasm_bindgen]
+pub fn parse_fixed_width(
+    input: &str,
+    widths: Vec<usize>,
+    has_header: Option<bool>,
+) -> Result<String, JsValue> {
+    let options = FixedWidthOptions {
+        widths,
+        has_header: has_header.unwrap_or(true),
+        ..Default::default()
+    };
+
+    parse_fixed_width_with(input, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Options for fixed-width parsing
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FixedWidthOptions {
+    /// Width of each column
+    pub widths: Vec<usize>,
+    /// Whether first row is header (default: true)
+    pub has_header: bool,
+    /// Convert strings to numbers/booleans (default: true)
+    pub convert_types: bool,
+    /// Measure widths in bytes instead of characters (default: false)
+    pub count_bytes: bool,
+    /// Treat lines shorter than the total width as an error instead of
+    /// padding the missing columns with empty values (default: false)
+    pub strict_line_length: bool,
+}
+
+impl Default for FixedWidthOptions {
+    fn default() -> Self {
+        Self {
+            widths: Vec::new(),
+            has_header: true,
+            convert_types: true,
+            count_bytes: false,
+            strict_line_length: false,
+        }
+    }
+}
+
+/// Parse fixed-width text to a JSON array with explicit options
+pub fn parse_fixed_width_with(input: &str, options: &FixedWidthOptions) -> Result<String, String> {
+    if options.widths.is_empty() {
+        return Err("At least one column width is required".to_string());
+    }
+
+    let mut lines = Vec::new();
+    for (line_no, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        lines.push(slice_line(line, line_no + 1, options)?);
+    }
+
+    let parse_options = ParseOptions {
+        has_header: options.has_header,
+        convert_types: options.convert_types,
+        ..ParseOptions::default()
+    };
+    let result = records_to_json(&lines, &parse_options)?;
+    serde_json::to_string(&result).map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Split one line into trimmed fields by column width
+fn slice_line(line: &str, line_no: usize, options: &FixedWidthOptions) -> Result<Vec<String>, String> {
+    // Byte offset of every character boundary (or every byte)
+    let boundaries: Vec<usize> = if options.count_bytes {
+        (0..=line.len()).collect()
+    } else {
+        line.char_indices().map(|(i, _)| i).chain(std::iter::once(line.len())).collect()
+    };
+    let units = boundaries.len() - 1;
+
+    let mut fields = Vec::with_capacity(options.widths.len());
+    let mut offset = 0;
+
+    for &width in &options.widths {
+        if offset + width > units && options.strict_line_length {
+            return Err(format!(
+                "Line {} is shorter than the expected {} columns",
+                line_no,
+                options.widths.iter().sum::<usize>()
+            ));
+        }
+
+        let start = boundaries[offset.min(units)];
+        let end = boundaries[(offset + width).min(units)];
+        let field = line
+            .get(start..end)
+            .ok_or_else(|| format!("Line {}: column boundary splits a character", line_no))?;
+        fields.push(field.trim().to_string());
+        offset += width;
+    }
+
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_columns() {
+        let input = "name  age\nAlice 30\nBob   25\n";
+        let options = FixedWidthOptions { widths: vec![6, 3], ..Default::default() };
+        let result = parse_fixed_width_with(input, &options).unwrap();
+        assert_eq!(result, r#"[{"name":"Alice","age":30},{"name":"Bob","age":25}]"#);
+    }
+
+    #[test]
+    fn test_short_lines_and_multibyte() {
+        let input = "José  4\nAl";
+        let options = FixedWidthOptions {
+            widths: vec![6, 1],
+            has_header: false,
+            ..Default::default()
+        };
+        let result = parse_fixed_width_with(input, &options).unwrap();
+        assert_eq!(result, r#"[["José",4],["Al",""]]"#);
+
+        let strict = FixedWidthOptions { strict_line_length: true, ..options.clone() };
+        assert!(parse_fixed_width_with(input, &strict).is_err());
+
+        let bytes = FixedWidthOptions { count_bytes: true, ..options };
+        let result = parse_fixed_width_with(input, &bytes).unwrap();
+        assert_eq!(result, r#"[["José",""],["Al",""]]"#);
+    }
+}
@@ -0,0 +1,109 @@
+//! Spreadsheet-style number formats
+//!
+//! Exported spreadsheets write numbers for people: `$1,234.50`, `45%`,
+//! `1.2K`. With `parse_formatted_numbers` these convert to plain numbers;
+//! anything that doesn't fit the shape exactly stays a string.
+
+use serde_json::{json, Value};
+
+use crate::ParseOptions;
+
+/// Convert a formatted number, or `None` if `s` isn't one unambiguously
+///
+/// Accepts an optional sign, one currency symbol from `currency_symbols`,
+/// digits with correctly grouped `,` thousands separators and an optional
+/// fraction, then either a `%` (divided by 100) or one suffix from
+/// `number_suffixes` (case-insensitive). Whole results become integers.
+pub(crate) fn parse_formatted_number(s: &str, options: &ParseOptions) -> Option<Value> {
+    let mut rest = s.trim();
+    let mut negative = false;
+    if let Some(unsigned) = rest.strip_prefix('-') {
+        negative = true;
+        rest = unsigned;
+    }
+    if let Some(symbol) = rest.chars().next().filter(|c| options.currency_symbols.contains(c)) {
+        rest = &rest[symbol.len_utf8()..];
+        if !negative {
+            if let Some(unsigned) = rest.strip_prefix('-') {
+                negative = true;
+                rest = unsigned;
+            }
+        }
+    }
+
+    let mut exponent = 0;
+    if let Some(body) = rest.strip_suffix('%') {
+        exponent = -2;
+        rest = body;
+    } else if let Some(last) = rest.chars().next_back() {
+        if let Some(&(_, power)) = options
+            .number_suffixes
+            .iter()
+            .find(|(suffix, _)| suffix.eq_ignore_ascii_case(&last))
+        {
+            exponent = power;
+            rest = &rest[..rest.len() - last.len_utf8()];
+        }
+    }
+
+    let digits = strip_thousands(rest)?;
+    // Scale in decimal so `1.2K` is exactly 1200
+    let mut value: f64 = format!("{}e{}", digits, exponent).parse().ok()?;
+    if negative {
+        value = -value;
+    }
+
+    if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+        Some(json!(value as i64))
+    } else {
+        serde_json::Number::from_f64(value).map(Value::Number)
+    }
+}
+
+/// Digits of `s` without thousands separators, if it is `1,234.5`-shaped
+fn strip_thousands(s: &str) -> Option<String> {
+    let (whole, fraction) = match s.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (s, None),
+    };
+    if fraction.is_some_and(|f| f.is_empty() || !f.bytes().all(|b| b.is_ascii_digit())) {
+        return None;
+    }
+
+    let mut groups = whole.split(',');
+    let first = groups.next()?;
+    let grouped = whole.contains(',');
+    let first_ok = !first.is_empty()
+        && first.bytes().all(|b| b.is_ascii_digit())
+        && (!grouped || first.len() <= 3);
+    if !first_ok || !groups.all(|g| g.len() == 3 && g.bytes().all(|b| b.is_ascii_digit())) {
+        return None;
+    }
+
+    let mut digits = whole.replace(',', "");
+    if let Some(fraction) = fraction {
+        digits.push('.');
+        digits.push_str(fraction);
+    }
+    Some(digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formatted_numbers() {
+        let options = ParseOptions::default();
+        let parse = |s| parse_formatted_number(s, &options);
+        assert_eq!(parse("$1,234.50"), Some(json!(1234.5)));
+        assert_eq!(parse("45%"), Some(json!(0.45)));
+        assert_eq!(parse("1.2K"), Some(json!(1200)));
+        assert_eq!(parse("-€3m"), Some(json!(-3_000_000)));
+        assert_eq!(parse("£-0.5"), Some(json!(-0.5)));
+
+        for ambiguous in ["$", "1,23", "12,3456", "1.2.3", "5%%", "1KM", "$$5", "1.", "abc"] {
+            assert_eq!(parse(ambiguous), None, "{}", ambiguous);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Gzip-compressed input for native callers (`gzip` feature)
+
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+
+use crate::{parse_csv_with, ParseOptions};
+
+/// Leading bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Inflate gzip-compressed CSV and parse it with explicit options
+pub fn parse_csv_gz(bytes: &[u8], options: &ParseOptions) -> Result<String, String> {
+    parse_csv_with(&inflate(bytes)?, options)
+}
+
+/// Decompress a gzip stream into UTF-8 text
+fn inflate(bytes: &[u8]) -> Result<String, String> {
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Err("Input is not gzip-compressed".to_string());
+    }
+
+    let mut text = String::new();
+    GzDecoder::new(bytes)
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Gzip error: {}", e))?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_csv_gz_round_trip() {
+        let csv = "name,age\nAlice,30\nBob,25";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(csv.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let options = ParseOptions::default();
+        assert_eq!(
+            parse_csv_gz(&compressed, &options).unwrap(),
+            parse_csv_with(csv, &options).unwrap()
+        );
+        assert_eq!(
+            parse_csv_gz(csv.as_bytes(), &options).unwrap_err(),
+            "Input is not gzip-compressed"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Error-tolerant CSV parsing
+//!
+//! Bulk imports often contain a handful of broken rows. Instead of failing
+//! the whole file, records go through the same state machine as `parse_csv`
+//! with error recovery on: when a record has a quoting error it is skipped
+//! and reported, and parsing resumes on the line after the one where the
+//! broken record started.
+
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+
+use crate::{for_each_record_lenient, records_to_json_quoted, ParseOptions};
+
+/// Parse CSV, skipping and reporting rows with quoting errors
+///
+/// Returns `{"rows": [...], "errors": [{"row": n, "message": "..."}]}` where
+/// `rows` has the same shape as `parse_csv` output and `row` is the 1-based
+/// line number on which the broken record starts.
+///
+/// # Arguments
+/// * `input` - CSV string to parse
+/// * `delimiter` - Field delimiter (default: ',')
+/// * `has_header` - Whether first row is header (default: true)
+/// * `convert_types` - Convert strings to numbers/booleans (default: true)
+#[wasm_bindgen]
+pub fn parse_csv_lenient(
+    input: &str,
+    delimiter: Option<char>,
+    has_header: Option<bool>,
+    convert_types: Option<bool>,
+) -> Result<String, JsValue> {
+    let defaults = ParseOptions::default();
+    let options = ParseOptions {
+        delimiter: delimiter.unwrap_or(defaults.delimiter),
+        has_header: has_header.unwrap_or(defaults.has_header),
+        convert_types: convert_types.unwrap_or(defaults.convert_types),
+        ..defaults
+    };
+
+    parse_csv_lenient_with(input, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Parse CSV with explicit options, skipping and reporting broken rows
+///
+/// All `parse_csv` options apply to the rows that parse cleanly.
+pub fn parse_csv_lenient_with(input: &str, options: &ParseOptions) -> Result<String, String> {
+    let mut lines = Vec::new();
+    let mut quoted = Vec::new();
+    let mut errors = Vec::new();
+    // Line breaks before `counted`, so line numbers are found incrementally
+    let mut counted = 0;
+    let mut line_no = 1;
+
+    for_each_record_lenient(
+        input,
+        options,
+        |record, record_quoted, _| {
+            lines.push(record);
+            quoted.push(record_quoted);
+        },
+        |start, message| {
+            line_no += input[counted..start].matches('\n').count();
+            counted = start;
+            errors.push(json!({ "row": line_no, "message": message }));
+        },
+    );
+
+    let rows = records_to_json_quoted(&lines, Some(&quoted), options)?;
+    let result = json!({ "rows": rows, "errors": errors });
+    serde_json::to_string(&result).map_err(|e| format!("Serialization error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn parse(input: &str, has_header: bool) -> Value {
+        let options = ParseOptions { has_header, ..ParseOptions::default() };
+        serde_json::from_str(&parse_csv_lenient_with(input, &options).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_skips_unterminated_quote() {
+        let input = "1,a\n2,b\n3,\"broken\n4,d\n";
+        let result = parse(input, false);
+        assert_eq!(result["rows"], json!([[1, "a"], [2, "b"], [4, "d"]]));
+        assert_eq!(result["errors"][0]["row"], 3);
+        assert_eq!(result["errors"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_quoted_newlines_and_stray_quotes() {
+        let input = "id,text\n1,\"multi\nline\"\n2,\"bad\"x\n3,ok\n";
+        let result = parse(input, true);
+        assert_eq!(
+            result["rows"],
+            json!([{"id": 1, "text": "multi\nline"}, {"id": 3, "text": "ok"}])
+        );
+        assert_eq!(result["errors"][0]["row"], 4);
+    }
+
+    #[test]
+    fn test_honors_parse_options() {
+        let options = ParseOptions {
+            delimiter: ';',
+            collapse_delimiters: true,
+            null_values: vec!["NA".to_string()],
+            ..ParseOptions::default()
+        };
+        let input = "\u{feff}id;;text\n1;;\"a;b\"\n2;\"x\"y\n3;NA\n4;\"open";
+        let result: Value = serde_json::from_str(&parse_csv_lenient_with(input, &options).unwrap()).unwrap();
+        assert_eq!(result["rows"], json!([{"id": 1, "text": "a;b"}, {"id": 3, "text": null}]));
+        assert_eq!(
+            result["errors"],
+            json!([
+                {"row": 3, "message": "Unexpected character 'y' after closing quote at byte 26"},
+                {"row": 5, "message": "Unterminated quoted field"},
+            ])
+        );
+
+        // Quotes are literal with backslash escapes, so nothing is malformed
+        let options = ParseOptions { backslash_escapes: true, has_header: false, ..ParseOptions::default() };
+        let result: Value = serde_json::from_str(&parse_csv_lenient_with("a\\,b,\"c", &options).unwrap()).unwrap();
+        assert_eq!(result, json!({"rows": [["a,b", "\"c"]], "errors": []}));
+    }
+}
@@ -7,9 +7,47 @@
 //! - Type conversion (numbers, booleans)
 //! - Header row support
 
+mod columnar;
+mod dialect;
+mod fixed_width;
+mod formatted;
+#[cfg(feature = "gzip")]
+mod gzip;
+mod lenient;
+mod profile;
+#[cfg(not(target_arch = "wasm32"))]
+mod reader;
+mod schema;
+mod table;
+mod writer;
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use molt_core::{classify_scalar, ScalarKind};
 use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+pub use columnar::{parse_csv_columnar_with, parse_csv_numeric};
+pub use dialect::{CsvDialect, Preset};
+pub use fixed_width::{parse_fixed_width_with, FixedWidthOptions};
+#[cfg(feature = "gzip")]
+pub use gzip::parse_csv_gz;
+pub use lenient::parse_csv_lenient_with;
+pub use profile::parse_csv_profile_with;
+#[cfg(not(target_arch = "wasm32"))]
+pub use reader::parse_csv_reader;
+pub use schema::{
+    parse_csv_with_schema_with, ColumnSchema, ColumnType, InvalidValuePolicy, SchemaOptions,
+};
+pub use table::{stringify_table_with, TableOptions};
+pub use writer::{stringify_csv_chunked, CsvWriter};
+
+// Re-exported for `parse_csv_with_spans` results
+pub use molt_core::Span;
+
 /// Parse CSV string to JSON array
 ///
 /// Returns a JSON string representing an array of objects (with header)
@@ -18,7 +56,7 @@ use serde_json::{json, Value};
 /// # Arguments
 /// * `input` - CSV string to parse
 /// * `delimiter` - Field delimiter (default: ',')
-/// * `has_header` - Whether first row is header (default: true)
+/// * `has_header` - Whether first row is header (default: detected with `sniff_has_header`)
 /// * `convert_types` - Convert strings to numbers/booleans (default: true)
 #[wasm_bindgen]
 pub fn parse_csv(
@@ -27,12 +65,444 @@ pub fn parse_csv(
     has_header: Option<bool>,
     convert_types: Option<bool>,
 ) -> Result<String, JsValue> {
-    let delimiter = delimiter.unwrap_or(',');
-    let has_header = has_header.unwrap_or(true);
-    let convert_types = convert_types.unwrap_or(true);
+    let defaults = ParseOptions::default();
+    let delimiter = delimiter.unwrap_or(defaults.delimiter);
+    let options = ParseOptions {
+        delimiter,
+        has_header: has_header.unwrap_or_else(|| sniff_has_header(input, Some(delimiter))),
+        convert_types: convert_types.unwrap_or(defaults.convert_types),
+        ..defaults
+    };
+
+    parse_csv_with(input, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Guess whether the first CSV row is a header
+///
+/// Compares the first row against up to 20 following rows. A text cell above
+/// a column of numbers or booleans marks a header; a typed cell above a typed
+/// column marks data. Without either signal the first row is a header when
+/// all its cells are text, so headerless all-text files are a false positive.
+///
+/// # Arguments
+/// * `input` - CSV string to inspect
+/// * `delimiter` - Field delimiter (default: ',')
+#[wasm_bindgen]
+pub fn sniff_has_header(input: &str, delimiter: Option<char>) -> bool {
+    let options = ParseOptions {
+        delimiter: delimiter.unwrap_or(','),
+        ..ParseOptions::default()
+    };
+    // Only the first row and the 20 sampled below it are split
+    let end = records_end(input, 21).unwrap_or(input.len());
+    let records = split_records(&input[..end], &options);
+    let Some((first, rest)) = records.split_first() else {
+        return true;
+    };
+    let sample = &rest[..rest.len().min(20)];
+    let is_text = |cell: &str| convert_value(cell, &options).is_string();
+
+    let typed_below = |col: usize| {
+        !sample.is_empty()
+            && sample
+                .iter()
+                .all(|row| row.get(col).is_some_and(|cell| !is_text(cell)))
+    };
+    let typed_columns: Vec<usize> = (0..first.len()).filter(|&col| typed_below(col)).collect();
+
+    if typed_columns.iter().any(|&col| is_text(&first[col])) {
+        return true;
+    }
+    if !typed_columns.is_empty() {
+        return false;
+    }
+    first.iter().all(|cell| is_text(cell))
+}
+
+/// Read only the header row and return its names as a JSON array
+///
+/// Bounded by the length of the first record, so it stays cheap on huge
+/// inputs; see `csv_headers`.
+///
+/// # Arguments
+/// * `input` - CSV string to inspect
+/// * `delimiter` - Field delimiter (default: ',')
+#[wasm_bindgen]
+pub fn parse_csv_headers(input: &str, delimiter: Option<char>) -> Result<String, JsValue> {
+    let headers = csv_headers(input, delimiter.unwrap_or(',')).map_err(|e| JsValue::from_str(&e))?;
+    serde_json::to_string(&headers)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Column names from the first record, without scanning the rest of the input
+///
+/// Quoted names may contain the delimiter or line breaks. A header whose
+/// quotes never close is an error; empty input has no names.
+pub fn csv_headers(input: &str, delimiter: char) -> Result<Vec<String>, String> {
+    let end = records_end(input, 1).ok_or("Unterminated quoted field in header row")?;
+    let options = ParseOptions { delimiter, ..ParseOptions::default() };
+    Ok(split_records(&input[..end], &options).into_iter().next().unwrap_or_default())
+}
+
+/// Byte offset of the `count`-th line break outside quotes, or the input
+/// length when there are fewer; a CRLF pair is one break
+///
+/// `None` when the input ends inside a quoted field.
+fn records_end(input: &str, count: usize) -> Option<usize> {
+    let bytes = input.as_bytes();
+    let mut in_quotes = false;
+    let mut breaks = 0;
+    for (i, &byte) in bytes.iter().enumerate() {
+        match byte {
+            b'"' => in_quotes = !in_quotes,
+            b'\n' if i > 0 && bytes[i - 1] == b'\r' => {}
+            b'\n' | b'\r' if !in_quotes => {
+                breaks += 1;
+                if breaks == count {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    (!in_quotes).then_some(input.len())
+}
+
+/// Parse CSV string to JSON array with an options object
+///
+/// `options_json` is a JSON object using camelCase field names, e.g.
+/// `{"delimiter": ";", "lenientEmbeddedQuotes": true}`.
+/// Unspecified fields use their defaults, or those of the `dialect` preset
+/// when one is named, e.g. `{"dialect": "postgresCopy"}`.
+#[wasm_bindgen]
+pub fn parse_csv_with_options(input: &str, options_json: &str) -> Result<String, JsValue> {
+    let options = dialect::options_from_json(options_json, |dialect| dialect.parse)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    parse_csv_with(input, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Options for parsing CSV
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ParseOptions {
+    /// Field delimiter (default: ',')
+    pub delimiter: char,
+    /// Whether first row is header (default: true)
+    pub has_header: bool,
+    /// Convert strings to numbers/booleans (default: true)
+    pub convert_types: bool,
+    /// Treat a `"` that is not at the start of an unquoted field as a literal character
+    /// (default: false)
+    pub lenient_embedded_quotes: bool,
+    /// Read `""` inside an unquoted field as one literal quote, so
+    /// `ab""cd` becomes `ab"cd` instead of opening a quoted section
+    /// (default: false)
+    pub lenient_doubled_quotes: bool,
+    /// Emit an object keyed by this column's value instead of an array (default: none)
+    pub key_column: Option<String>,
+    /// How repeated `key_column` values are handled (default: last wins)
+    pub duplicate_keys: DuplicateKeyPolicy,
+    /// Only emit these columns, in this order (default: all columns)
+    pub select_columns: Option<Vec<String>>,
+    /// Ignore selected columns missing from the header instead of failing (default: false)
+    pub skip_missing_columns: bool,
+    /// Treat runs of unquoted delimiters as one, ignoring leading and trailing
+    /// delimiters, like `awk` field splitting; quoted empty fields are kept
+    /// (default: false)
+    pub collapse_delimiters: bool,
+    /// Accept `_` between digits as a separator when converting numbers, so
+    /// `1_000` becomes `1000` (default: false)
+    pub allow_underscore_digits: bool,
+    /// Number of header rows; with more than one, each column's header cells
+    /// are joined with `header_separator`, e.g. `Q1.rev` (default: 1)
+    pub header_rows: usize,
+    /// Separator for multi-row header names (default: '.')
+    pub header_separator: char,
+    /// Keep fields that were quoted in the input as strings instead of
+    /// converting them, so `"42"` stays `"42"` (default: false)
+    pub quoted_as_strings: bool,
+    /// Open and close characters that wrap a field like quotes, e.g.
+    /// `('{', '}')`; nested pairs inside a field are kept, the outer pair is
+    /// stripped, and wrapped fields count as quoted
+    pub field_wrapper: Option<(char, char)>,
+    /// Convert spreadsheet-formatted numbers such as `$1,234.50`, `45%` or
+    /// `1.2K` (default: false)
+    pub parse_formatted_numbers: bool,
+    /// Currency symbols stripped by `parse_formatted_numbers`
+    /// (default: `$`, `€`, `£`)
+    pub currency_symbols: Vec<char>,
+    /// Magnitude suffixes for `parse_formatted_numbers` as (suffix, power of
+    /// ten) pairs (default: `K` 3, `M` 6, `B` 9)
+    pub number_suffixes: Vec<(char, i32)>,
+    /// Group consecutive rows with the same value in this column into
+    /// `{"<column>": value, "items": [...]}` records (default: none)
+    pub group_by: Option<String>,
+    /// Further columns kept once on each group instead of in its items
+    /// (default: none)
+    pub group_columns: Vec<String>,
+    /// Add rows to an earlier group with the same key even when they are not
+    /// contiguous, instead of starting a new group (default: false)
+    pub merge_groups: bool,
+    /// Unquoted fields exactly equal to one of these become `null`, e.g.
+    /// `\N` for PostgreSQL `COPY` output (default: none)
+    pub null_values: Vec<String>,
+    /// Read PostgreSQL `COPY` text fields: `\t`, `\n`, `\r`, `\\` and an
+    /// escaped delimiter are unescaped, `\N` is kept as written so
+    /// `null_values` can match it, and quotes are literal (default: false)
+    pub backslash_escapes: bool,
+}
+
+/// Policy for repeated values in the key column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateKeyPolicy {
+    /// Later rows replace earlier rows with the same key
+    #[default]
+    LastWins,
+    /// Repeated keys are an error
+    Error,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            has_header: true,
+            convert_types: true,
+            lenient_embedded_quotes: false,
+            lenient_doubled_quotes: false,
+            key_column: None,
+            duplicate_keys: DuplicateKeyPolicy::LastWins,
+            select_columns: None,
+            skip_missing_columns: false,
+            collapse_delimiters: false,
+            allow_underscore_digits: false,
+            header_rows: 1,
+            header_separator: '.',
+            quoted_as_strings: false,
+            field_wrapper: None,
+            parse_formatted_numbers: false,
+            currency_symbols: vec!['$', '€', '£'],
+            number_suffixes: vec![('K', 3), ('M', 6), ('B', 9)],
+            group_by: None,
+            group_columns: Vec::new(),
+            merge_groups: false,
+            null_values: Vec::new(),
+            backslash_escapes: false,
+        }
+    }
+}
+
+/// Parse CSV string to JSON array with explicit options
+pub fn parse_csv_with(input: &str, options: &ParseOptions) -> Result<String, String> {
+    parse_csv_internal(input, options)
+}
+
+/// Parse CSV, passing every field through a JS callback before type conversion
+///
+/// `cell_fn(column, value)` must return a string; `column` is the header name,
+/// or the 0-based column index when there is no header row.
+///
+/// # Arguments
+/// * `input` - CSV string to parse
+/// * `delimiter` - Field delimiter (default: ',')
+/// * `has_header` - Whether first row is header (default: true)
+/// * `cell_fn` - Transform called for every data field
+#[wasm_bindgen]
+pub fn parse_csv_with_transform(
+    input: &str,
+    delimiter: Option<char>,
+    has_header: Option<bool>,
+    cell_fn: &js_sys::Function,
+) -> Result<String, JsValue> {
+    let defaults = ParseOptions::default();
+    let options = ParseOptions {
+        delimiter: delimiter.unwrap_or(defaults.delimiter),
+        has_header: has_header.unwrap_or(defaults.has_header),
+        ..defaults
+    };
+
+    let mut error = None;
+    let result = parse_csv_transform_with(input, &options, |column, value| {
+        if error.is_some() {
+            return value.to_string();
+        }
+        let returned =
+            cell_fn.call2(&JsValue::NULL, &JsValue::from_str(column), &JsValue::from_str(value));
+        match returned.map(|v| v.as_string()) {
+            Ok(Some(s)) => s,
+            Ok(None) => {
+                error = Some(JsValue::from_str("Transform must return a string"));
+                value.to_string()
+            }
+            Err(e) => {
+                error = Some(e);
+                value.to_string()
+            }
+        }
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => result.map_err(|e| JsValue::from_str(&e)),
+    }
+}
+
+/// Parse CSV with explicit options, transforming every data field first
+///
+/// `cell_fn(column, value)` receives the header name (or the 0-based column
+/// index without a header) and the raw field text, and returns the text that
+/// is then type-converted as usual. Header fields are not transformed.
+pub fn parse_csv_transform_with<F>(
+    input: &str,
+    options: &ParseOptions,
+    mut cell_fn: F,
+) -> Result<String, String>
+where
+    F: FnMut(&str, &str) -> String,
+{
+    let mut lines = split_records(input, options);
+    let (header, rows) = if options.has_header && !lines.is_empty() {
+        let (header, rows) = lines.split_at_mut(1);
+        (Some(&header[0]), rows)
+    } else {
+        (None, &mut lines[..])
+    };
+
+    for row in rows {
+        for (i, field) in row.iter_mut().enumerate() {
+            let index;
+            let column = match header.and_then(|h| h.get(i)) {
+                Some(name) => name.as_str(),
+                None => {
+                    index = i.to_string();
+                    &index
+                }
+            };
+            *field = cell_fn(column, field);
+        }
+    }
+
+    let result = records_to_json(&lines, options)?;
+    serde_json::to_string(&result).map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Parse CSV, keeping only the rows a JS predicate accepts
+///
+/// `predicate(row)` receives each data row as an object (or an array without
+/// a header); rows for which it returns a falsy value are left out. Rows are
+/// handed over as JS values as they are parsed; only accepted rows are
+/// serialized.
+///
+/// # Arguments
+/// * `input` - CSV string to parse
+/// * `delimiter` - Field delimiter (default: ',')
+/// * `has_header` - Whether first row is header (default: true)
+/// * `predicate` - Called with every data row
+#[wasm_bindgen]
+pub fn parse_csv_filter(
+    input: &str,
+    delimiter: Option<char>,
+    has_header: Option<bool>,
+    predicate: &js_sys::Function,
+) -> Result<String, JsValue> {
+    let defaults = ParseOptions::default();
+    let options = ParseOptions {
+        delimiter: delimiter.unwrap_or(defaults.delimiter),
+        has_header: has_header.unwrap_or(defaults.has_header),
+        ..defaults
+    };
+
+    let mut error = None;
+    let result = parse_csv_filter_with(input, &options, |row| {
+        if error.is_some() {
+            return false;
+        }
+        match predicate.call1(&JsValue::NULL, &value_to_js(row)) {
+            Ok(keep) => keep.is_truthy(),
+            Err(e) => {
+                error = Some(e);
+                false
+            }
+        }
+    });
 
-    parse_csv_internal(input, delimiter, has_header, convert_types)
-        .map_err(|e| JsValue::from_str(&e))
+    match error {
+        Some(e) => Err(e),
+        None => result.map_err(|e| JsValue::from_str(&e)),
+    }
+}
+
+/// Build the JS value for a parsed row without a JSON round trip
+fn value_to_js(value: &Value) -> JsValue {
+    match value {
+        Value::Null => JsValue::NULL,
+        Value::Bool(b) => JsValue::from_bool(*b),
+        Value::Number(n) => n.as_f64().map_or(JsValue::NULL, JsValue::from_f64),
+        Value::String(s) => JsValue::from_str(s),
+        Value::Array(items) => items.iter().map(value_to_js).collect::<js_sys::Array>().into(),
+        Value::Object(map) => {
+            let object = js_sys::Object::new();
+            for (key, value) in map {
+                // Setting a property on a fresh plain object cannot fail
+                let _ = js_sys::Reflect::set(&object, &JsValue::from_str(key), &value_to_js(value));
+            }
+            object.into()
+        }
+    }
+}
+
+/// Parse CSV with explicit options, keeping only rows `predicate` accepts
+///
+/// Rows are converted and tested as the splitter completes each record, so
+/// the file is never collected into records and rejected rows are never
+/// serialized. Rows are shaped as by `parse_csv`, honoring `null_values`,
+/// `quoted_as_strings`, `select_columns` and `header_rows`; the grouping
+/// and keyed-object options don't apply.
+pub fn parse_csv_filter_with<F>(
+    input: &str,
+    options: &ParseOptions,
+    mut predicate: F,
+) -> Result<String, String>
+where
+    F: FnMut(&Value) -> bool,
+{
+    let mut rows = RowStream::new(options)?;
+    let mut output = String::from("[");
+    let mut error = None;
+
+    for_each_record(input, options, |record, quoted, _| {
+        if error.is_some() {
+            return;
+        }
+        let row = match rows.push(record, &quoted) {
+            Ok(Some(row)) => row,
+            Ok(None) => return,
+            Err(e) => {
+                error = Some(e);
+                return;
+            }
+        };
+        if !predicate(&row) {
+            return;
+        }
+        if output.len() > 1 {
+            output.push(',');
+        }
+        match serde_json::to_string(&row) {
+            Ok(json) => output.push_str(&json),
+            Err(e) => error = Some(format!("Serialization error: {}", e)),
+        }
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => {
+            output.push(']');
+            Ok(output)
+        }
+    }
 }
 
 /// Stringify JSON array to CSV string
@@ -51,178 +521,897 @@ pub fn stringify_csv(
     include_header: Option<bool>,
     quote_all: Option<bool>,
 ) -> Result<String, JsValue> {
-    let delimiter = delimiter.unwrap_or(',');
-    let include_header = include_header.unwrap_or(true);
-    let quote_all = quote_all.unwrap_or(false);
+    let defaults = StringifyOptions::default();
+    let options = StringifyOptions {
+        delimiter: delimiter.unwrap_or(defaults.delimiter),
+        include_header: include_header.unwrap_or(defaults.include_header),
+        quote_all: quote_all.unwrap_or(defaults.quote_all),
+        ..defaults
+    };
+
+    let data: Value = serde_json::from_str(json_input)
+        .map_err(|e| JsValue::from_str(&format!("Invalid JSON: {}", e)))?;
+
+    stringify_csv_with(&data, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Stringify JSON array to CSV string with an options object
+///
+/// `options_json` is a JSON object using camelCase field names, e.g.
+/// `{"delimiter": ";", "melt": ["id"]}`. Unspecified fields use their
+/// defaults, or those of the `dialect` preset when one is named.
+#[wasm_bindgen]
+pub fn stringify_csv_with_options(json_input: &str, options_json: &str) -> Result<String, JsValue> {
+    let options = dialect::options_from_json(options_json, |dialect| dialect.stringify)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let data: Value = serde_json::from_str(json_input)
+        .map_err(|e| JsValue::from_str(&format!("Invalid JSON: {}", e)))?;
+
+    stringify_csv_with(&data, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Stringify JSON array to CSV, passing the text to `on_chunk` in pieces
+///
+/// Each chunk holds whole rows and is emitted once it reaches `chunk_size`
+/// bytes; concatenated, the chunks equal the `stringify_csv` output.
+///
+/// # Arguments
+/// * `json_input` - JSON string to stringify
+/// * `options_json` - Options object as for `stringify_csv_with_options`
+/// * `chunk_size` - Byte threshold at which a chunk is emitted
+/// * `on_chunk` - Called with each chunk string
+#[wasm_bindgen]
+pub fn stringify_csv_chunks(
+    json_input: &str,
+    options_json: &str,
+    chunk_size: usize,
+    on_chunk: &js_sys::Function,
+) -> Result<(), JsValue> {
+    let options = dialect::options_from_json(options_json, |dialect| dialect.stringify)
+        .map_err(|e| JsValue::from_str(&e))?;
 
     let data: Value = serde_json::from_str(json_input)
         .map_err(|e| JsValue::from_str(&format!("Invalid JSON: {}", e)))?;
 
-    stringify_csv_internal(&data, delimiter, include_header, quote_all)
-        .map_err(|e| JsValue::from_str(&e))
+    let mut error = None;
+    stringify_csv_chunked(&data, &options, chunk_size, |chunk| {
+        if error.is_none() {
+            error = on_chunk.call1(&JsValue::NULL, &JsValue::from_str(chunk)).err();
+        }
+    })
+    .map_err(|e| JsValue::from_str(&e))?;
+
+    error.map_or(Ok(()), Err)
+}
+
+/// Options for stringifying CSV
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StringifyOptions {
+    /// Field delimiter (default: ',')
+    pub delimiter: char,
+    /// Whether to include header row (default: true)
+    pub include_header: bool,
+    /// Quote all fields (default: false)
+    pub quote_all: bool,
+    /// Reshape objects to long format, keeping these id columns and emitting
+    /// one `variable`/`value` row per remaining key (default: none)
+    pub melt: Option<Vec<String>>,
+    /// Use CRLF record terminators and also quote ambiguous fields
+    /// (see `is_ambiguous_field`) (default: false)
+    pub strict_rfc4180: bool,
+    /// How array and object values are written into a cell (default: compact JSON)
+    pub nested_encoding: NestedEncoding,
+    /// Separator used by `NestedEncoding::SemicolonJoined` (default: ';')
+    pub nested_join: char,
+    /// Case applied to header names; data lookups still use the original keys
+    /// (default: as-is)
+    pub header_transform: HeaderTransform,
+    /// Quote string values that type conversion would read back as a number
+    /// or boolean, e.g. the string `"42"`; pair with `quoted_as_strings` when
+    /// parsing (default: false)
+    pub quote_typed_strings: bool,
+    /// Quote fields containing a comma even when the delimiter is something
+    /// else, for consumers that split on commas regardless; by default only
+    /// the actual delimiter forces quoting, so `a,b` stays bare in TSV
+    /// (default: false)
+    pub quote_commas_always: bool,
+    /// Sort object rows by this column before writing, numerically when all
+    /// its values are numbers and as text otherwise (default: none)
+    pub sort_by: Option<(String, SortOrder)>,
+    /// With `sort_by`, put rows whose column is null or missing first
+    /// instead of last (default: false)
+    pub nulls_first: bool,
+    /// Annotate each header name with its column type, e.g. `age Int64`;
+    /// types come from `column_types` or are inferred from the values
+    /// (default: false)
+    pub typed_header: bool,
+    /// Declared column types for `typed_header`, overriding inference
+    /// (default: none)
+    pub column_types: HashMap<String, ColumnType>,
+    /// Type names for `typed_header`; unmapped types use ClickHouse names
+    /// (`String`, `Int64`, `Float64`, `Bool`) (default: none)
+    pub type_names: HashMap<ColumnType, String>,
+    /// Start the output with a UTF-8 byte order mark, which Excel needs to
+    /// detect the encoding (default: false)
+    pub bom: bool,
+    /// Text written for null and missing values (default: empty)
+    pub null_value: String,
+    /// Escape backslashes, tabs and line breaks with backslash sequences
+    /// instead of quoting, as PostgreSQL `COPY` text format expects
+    /// (default: false)
+    pub backslash_escapes: bool,
+    /// Text written for `true` and `false` values, e.g. `("1", "0")` or
+    /// `("Yes", "No")` (default: `true`, `false`)
+    pub bool_output: (String, String),
+}
+
+/// Direction for `sort_by`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Case transform for the header row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HeaderTransform {
+    #[default]
+    AsIs,
+    Upper,
+    Lower,
+    /// `first_name` -> `First Name`
+    Title,
+}
+
+impl HeaderTransform {
+    /// Apply the transform to one header name
+    pub fn apply<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        match self {
+            HeaderTransform::AsIs => Cow::Borrowed(name),
+            HeaderTransform::Upper => Cow::Owned(name.to_uppercase()),
+            HeaderTransform::Lower => Cow::Owned(name.to_lowercase()),
+            HeaderTransform::Title => Cow::Owned(molt_core::to_title_case(name)),
+        }
+    }
+}
+
+/// Encoding for array and object values inside a single CSV cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NestedEncoding {
+    /// Compact JSON, e.g. `["a","b"]`
+    #[default]
+    Json,
+    /// Indented JSON
+    JsonPretty,
+    /// Arrays of scalars joined with `nested_join`, e.g. `a;b`;
+    /// other nested values fall back to compact JSON
+    SemicolonJoined,
+    /// Python literal syntax, e.g. `['a', 'b']` or `{'k': None}`
+    PythonRepr,
+}
+
+impl Default for StringifyOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            include_header: true,
+            quote_all: false,
+            melt: None,
+            strict_rfc4180: false,
+            nested_encoding: NestedEncoding::Json,
+            nested_join: ';',
+            header_transform: HeaderTransform::AsIs,
+            quote_typed_strings: false,
+            quote_commas_always: false,
+            sort_by: None,
+            nulls_first: false,
+            typed_header: false,
+            column_types: HashMap::new(),
+            type_names: HashMap::new(),
+            bom: false,
+            null_value: String::new(),
+            backslash_escapes: false,
+            bool_output: ("true".to_string(), "false".to_string()),
+        }
+    }
+}
+
+/// Stringify a JSON array to CSV with explicit options
+pub fn stringify_csv_with(data: &Value, options: &StringifyOptions) -> Result<String, String> {
+    stringify_csv_internal(data, options)
 }
 
 // ============================================================================
 // Internal Implementation
 // ============================================================================
 
-fn parse_csv_internal(
+fn parse_csv_internal(input: &str, options: &ParseOptions) -> Result<String, String> {
+    let needs_quoted =
+        (options.quoted_as_strings && options.convert_types) || !options.null_values.is_empty();
+    let result = if needs_quoted {
+        let mut quoted = Vec::new();
+        let lines = split_records_inner(input, options, Some(&mut quoted), None);
+        records_to_json_quoted(&lines, Some(&quoted), options)?
+    } else {
+        records_to_json(&split_records(input, options), options)?
+    };
+    serde_json::to_string(&result).map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Split CSV text into records of raw field strings
+///
+/// Inside a quoted field each run of `n` quotes yields `n / 2` literal quotes
+/// and closes the field when `n` is odd. A field left open by an even run at
+/// the very end of the input is closed by that run's last quote, so `"a""`
+/// parses as `a"` rather than as an unterminated field.
+pub(crate) fn split_records(input: &str, options: &ParseOptions) -> Vec<Vec<String>> {
+    split_records_inner(input, options, None, None)
+}
+
+/// Split CSV text into records of raw fields with their source spans
+///
+/// Each span covers the field as written, including any surrounding quotes
+/// or `field_wrapper` pair, so `&input[span.start..span.end]` is the
+/// original cell text. The header row, if any, is the first record; values
+/// are not type-converted.
+pub fn parse_csv_with_spans(input: &str, options: &ParseOptions) -> Vec<Vec<(String, Span)>> {
+    let mut spans = Vec::new();
+    let records = split_records_inner(input, options, None, Some(&mut spans));
+    records
+        .into_iter()
+        .zip(spans)
+        .map(|(fields, spans)| fields.into_iter().zip(spans).collect())
+        .collect()
+}
+
+/// Split CSV text into records, optionally recording which fields were quoted
+fn split_records_inner(
     input: &str,
-    delimiter: char,
-    has_header: bool,
-    convert_types: bool,
-) -> Result<String, String> {
+    options: &ParseOptions,
+    mut quoted: Option<&mut Vec<Vec<bool>>>,
+    mut spans: Option<&mut Vec<Vec<Span>>>,
+) -> Vec<Vec<String>> {
     let mut lines = Vec::new();
+    for_each_record(input, options, |record, record_quoted, record_spans| {
+        lines.push(record);
+        if let Some(quoted) = quoted.as_mut() {
+            quoted.push(record_quoted);
+        }
+        if let Some(spans) = spans.as_mut() {
+            spans.push(record_spans);
+        }
+    });
+    lines
+}
+
+/// Split CSV text, handing each record to `on_record` as soon as it ends
+///
+/// The callback receives the raw fields with their quoted flags and spans,
+/// so callers can process rows without collecting the whole file first.
+pub(crate) fn for_each_record<F>(input: &str, options: &ParseOptions, on_record: F)
+where
+    F: FnMut(Vec<String>, Vec<bool>, Vec<Span>),
+{
+    split_each(input, options, on_record, None::<fn(usize, String)>);
+}
+
+/// Split CSV text like `for_each_record`, reporting malformed records
+///
+/// A record with a quote inside an unquoted field, text after a closing
+/// quote, or a quoted field still open at end of input is dropped:
+/// `on_error` receives the byte offset where the record started and a
+/// message, and splitting resumes on the line after that offset.
+pub(crate) fn for_each_record_lenient<F, E>(input: &str, options: &ParseOptions, on_record: F, on_error: E)
+where
+    F: FnMut(Vec<String>, Vec<bool>, Vec<Span>),
+    E: FnMut(usize, String),
+{
+    split_each(input, options, on_record, Some(on_error));
+}
+
+/// The record state machine; malformed quoting is only detected, and
+/// recovered from, when `on_error` is given
+fn split_each<F, E>(input: &str, options: &ParseOptions, mut on_record: F, mut on_error: Option<E>)
+where
+    F: FnMut(Vec<String>, Vec<bool>, Vec<Span>),
+    E: FnMut(usize, String),
+{
+    let delimiter = options.delimiter;
     let mut current_line = Vec::new();
+    let mut current_quoted = Vec::new();
+    let mut current_spans = Vec::new();
     let mut current_field = String::new();
     let mut in_quotes = false;
+    let mut field_quoted = false;
+    // Whether the current field's closing quote was the last character
+    let mut after_quote = false;
+    // Nesting depth inside a `field_wrapper` pair; 0 when outside
+    let mut wrap_depth = 0usize;
     let mut chars = input.chars().peekable();
+    // Byte offset just past the last consumed character, and where the
+    // current field and record started
+    let mut pos = 0;
+    let mut field_start = 0;
+    let mut record_start = 0;
+    // Message for a malformed record found by a recovering split
+    let mut failure: Option<String> = None;
+
+    // A byte order mark is never part of the first field
+    if chars.next_if_eq(&'\u{feff}').is_some() {
+        pos = '\u{feff}'.len_utf8();
+        field_start = pos;
+        record_start = pos;
+    }
+
+    // With collapse_delimiters, empty unquoted fields (from repeated, leading
+    // or trailing delimiters) are dropped
+    let collapse = |field: &String, quoted: bool| {
+        options.collapse_delimiters && field.is_empty() && !quoted
+    };
 
     // Parse all rows
-    while let Some(ch) = chars.next() {
-        if in_quotes {
+    loop {
+        if let Some(message) = failure.take() {
+            if let Some(on_error) = on_error.as_mut() {
+                on_error(record_start, message);
+            }
+            // Drop the broken record and resume on the line after its start
+            let Some(offset) = input[record_start..].find('\n') else {
+                return;
+            };
+            pos = record_start + offset + 1;
+            chars = input[pos..].chars().peekable();
+            field_start = pos;
+            record_start = pos;
+            current_line.clear();
+            current_quoted.clear();
+            current_spans.clear();
+            current_field.clear();
+            in_quotes = false;
+            field_quoted = false;
+            after_quote = false;
+            wrap_depth = 0;
+        }
+
+        let Some(ch) = chars.next() else {
+            if in_quotes && on_error.is_some() {
+                failure = Some("Unterminated quoted field".to_string());
+                continue;
+            }
+            break;
+        };
+        let at = pos;
+        pos += ch.len_utf8();
+        if wrap_depth > 0 {
+            let (open, close) = options.field_wrapper.unwrap_or_default();
+            if ch == close {
+                wrap_depth -= 1;
+            } else if ch == open {
+                wrap_depth += 1;
+            }
+            if wrap_depth > 0 {
+                current_field.push(ch);
+            }
+        } else if in_quotes {
             if ch == '"' {
-                // Check for escaped quote (doubled quotes)
-                if chars.peek() == Some(&'"') {
+                let run = 1 + count_quote_run(&mut chars);
+                pos += run - 1;
+                // Doubled quotes are literal; an odd run ends with the closing
+                // quote. An even run at end of input leaves the field unclosed,
+                // so its last quote closes it and the lone leftover is literal.
+                for _ in 0..run / 2 {
                     current_field.push('"');
-                    chars.next();
-                } else {
-                    in_quotes = false;
                 }
+                in_quotes = run.is_multiple_of(2) && chars.peek().is_some();
+                after_quote = !in_quotes;
             } else {
                 current_field.push(ch);
             }
         } else {
             match ch {
-                '"' => in_quotes = true,
-                c if c == delimiter => {
-                    current_line.push(current_field.clone());
-                    current_field.clear();
+                c if after_quote && on_error.is_some() && !matches!(c, '\n' | '\r') && c != delimiter => {
+                    failure = Some(format!("Unexpected character '{}' after closing quote at byte {}", c, at));
                 }
-                '\n' => {
-                    current_line.push(current_field.clone());
-                    current_field.clear();
-                    if !current_line.is_empty() {
-                        lines.push(current_line.clone());
-                        current_line.clear();
+                c if current_field.is_empty()
+                    && !field_quoted
+                    && options.field_wrapper.is_some_and(|(open, _)| c == open) =>
+                {
+                    wrap_depth = 1;
+                    field_quoted = true;
+                }
+                '\\' if options.backslash_escapes => {
+                    let Some(escaped) = chars.next() else {
+                        current_field.push(ch);
+                        continue;
+                    };
+                    pos += escaped.len_utf8();
+                    match escaped {
+                        'n' => current_field.push('\n'),
+                        'r' => current_field.push('\r'),
+                        't' => current_field.push('\t'),
+                        'N' => current_field.push_str("\\N"),
+                        c => current_field.push(c),
+                    }
+                }
+                '"' if options.lenient_doubled_quotes
+                    && !current_field.is_empty()
+                    && chars.next_if_eq(&'"').is_some() =>
+                {
+                    pos += 1;
+                    current_field.push('"');
+                }
+                '"' if options.backslash_escapes
+                    || (options.lenient_embedded_quotes && !current_field.is_empty()) =>
+                {
+                    current_field.push(ch);
+                }
+                '"' if on_error.is_some() && !current_field.is_empty() => {
+                    failure = Some(format!("Unexpected quote at byte {}", at));
+                }
+                '"' => {
+                    in_quotes = true;
+                    field_quoted = true;
+                }
+                c if c == delimiter => {
+                    if !collapse(&current_field, field_quoted) {
+                        current_line.push(current_field.clone());
+                        current_quoted.push(field_quoted);
+                        current_spans.push(Span::new(field_start, at));
                     }
+                    current_field.clear();
+                    field_quoted = false;
+                    after_quote = false;
+                    field_start = pos;
                 }
-                '\r' => {
+                '\n' | '\r' => {
                     // Handle CRLF
-                    if chars.peek() == Some(&'\n') {
-                        chars.next();
+                    if ch == '\r' && chars.next_if_eq(&'\n').is_some() {
+                        pos += 1;
+                    }
+                    if !collapse(&current_field, field_quoted) || current_line.is_empty() {
+                        current_line.push(current_field.clone());
+                        current_quoted.push(field_quoted);
+                        current_spans.push(Span::new(field_start, at));
                     }
-                    current_line.push(current_field.clone());
                     current_field.clear();
+                    field_quoted = false;
+                    field_start = pos;
                     if !current_line.is_empty() {
-                        lines.push(current_line.clone());
-                        current_line.clear();
+                        on_record(
+                            std::mem::take(&mut current_line),
+                            std::mem::take(&mut current_quoted),
+                            std::mem::take(&mut current_spans),
+                        );
                     }
+                    current_quoted.clear();
+                    current_spans.clear();
+                    after_quote = false;
+                    record_start = pos;
+                }
+                _ => current_field.push(ch),
+            }
+        }
+    }
+
+    // Handle last field and line
+    if !current_field.is_empty() || !current_line.is_empty() {
+        if !collapse(&current_field, field_quoted) || current_line.is_empty() {
+            current_line.push(current_field);
+            current_quoted.push(field_quoted);
+            current_spans.push(Span::new(field_start, input.len()));
+        }
+        if !current_line.is_empty() {
+            on_record(current_line, current_quoted, current_spans);
+        }
+    }
+}
+
+/// Consume the quotes following a quote inside a quoted field, returning how many
+fn count_quote_run<I: Iterator<Item = char>>(chars: &mut std::iter::Peekable<I>) -> usize {
+    let mut count = 0;
+    while chars.next_if_eq(&'"').is_some() {
+        count += 1;
+    }
+    count
+}
+
+/// Assemble parsed records into a JSON value according to the options
+pub(crate) fn records_to_json(lines: &[Vec<String>], options: &ParseOptions) -> Result<Value, String> {
+    records_to_json_quoted(lines, None, options)
+}
+
+/// Assemble parsed records, leaving fields flagged in `quoted` unconverted
+pub(crate) fn records_to_json_quoted(
+    lines: &[Vec<String>],
+    quoted: Option<&[Vec<bool>]>,
+    options: &ParseOptions,
+) -> Result<Value, String> {
+    let has_header = options.has_header;
+
+    if lines.is_empty() {
+        return Ok(json!([]));
+    }
+
+    let merged;
+    let (lines, quoted) = if has_header && options.header_rows > 1 {
+        merged = merge_header_rows(lines, options.header_rows, options.header_separator);
+        let merged_away = options.header_rows.min(lines.len()) - 1;
+        (&merged[..], quoted.map(|q| &q[merged_away.min(q.len())..]))
+    } else {
+        (lines, quoted)
+    };
+    let cells = CellConverter { options, quoted };
+
+    // Convert to JSON
+    let result = if let Some(key_column) = &options.key_column {
+        if !has_header {
+            return Err("key_column requires a header row".to_string());
+        }
+        records_to_keyed_object(lines, key_column, &cells)?
+    } else if let Some(group_by) = &options.group_by {
+        if !has_header {
+            return Err("group_by requires a header row".to_string());
+        }
+        records_to_groups(lines, group_by, &cells)?
+    } else {
+        let (header, data_rows) = match has_header {
+            true => (Some(lines[0].clone()), &lines[1..]),
+            false => (None, lines),
+        };
+        let first_row = usize::from(has_header);
+        let rows = RowBuilder::new(header, options)?;
+        let quoted_row = |i: usize| quoted.and_then(|q| q.get(first_row + i)).map_or(&[][..], Vec::as_slice);
+
+        data_rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| rows.build(row, quoted_row(i)))
+            .collect()
+    };
+
+    Ok(result)
+}
+
+/// Per-cell type conversion honoring `convert_types` and quoted fields
+struct CellConverter<'a> {
+    options: &'a ParseOptions,
+    /// Quoted-field flags by record, when quoted fields stay strings or
+    /// `null_values` apply
+    quoted: Option<&'a [Vec<bool>]>,
+}
+
+impl CellConverter<'_> {
+    fn convert(&self, value: &str, row: usize, col: usize) -> Value {
+        let quoted = self
+            .quoted
+            .and_then(|quoted| quoted.get(row)?.get(col).copied())
+            .unwrap_or(false);
+        convert_cell(value, quoted, self.options)
+    }
+}
+
+/// Convert one cell, given whether it was quoted in the source
+fn convert_cell(value: &str, quoted: bool, options: &ParseOptions) -> Value {
+    if !quoted && options.null_values.iter().any(|null| null == value) {
+        Value::Null
+    } else if options.convert_types && !(quoted && options.quoted_as_strings) {
+        convert_value(value, options)
+    } else {
+        Value::String(value.to_string())
+    }
+}
+
+/// Shapes data records into row values the way `parse_csv` does
+///
+/// With a header each row is an object keyed by it, limited to
+/// `select_columns` when set; without one it is an array.
+struct RowBuilder<'a> {
+    options: &'a ParseOptions,
+    header: Option<Vec<String>>,
+    selected: Option<Vec<usize>>,
+}
+
+impl<'a> RowBuilder<'a> {
+    fn new(header: Option<Vec<String>>, options: &'a ParseOptions) -> Result<Self, String> {
+        let selected = match &header {
+            Some(header) => select_indices(header, options)?,
+            None => None,
+        };
+        Ok(Self { options, header, selected })
+    }
+
+    /// Convert one record; `quoted` flags its quoted fields and may be short
+    fn build(&self, record: &[String], quoted: &[bool]) -> Value {
+        let cell = |i: usize| {
+            convert_cell(&record[i], quoted.get(i).copied().unwrap_or(false), self.options)
+        };
+        let Some(header) = &self.header else {
+            return (0..record.len()).map(cell).collect();
+        };
+
+        let mut obj = serde_json::Map::new();
+        if let Some(indices) = &self.selected {
+            // Only assemble the projected columns, in selection order
+            for &i in indices.iter().filter(|&&i| i < record.len()) {
+                obj.insert(header[i].clone(), cell(i));
+            }
+        } else {
+            for i in 0..record.len() {
+                obj.insert(header.get(i).cloned().unwrap_or_default(), cell(i));
+            }
+        }
+        Value::Object(obj)
+    }
+}
+
+/// Turns records into data rows one at a time, reading the header first
+///
+/// The first `header_rows` records (when `has_header` is set) are merged
+/// into the header; every later record becomes a row.
+pub(crate) struct RowStream<'a> {
+    options: &'a ParseOptions,
+    header_lines: Vec<Vec<String>>,
+    rows: Option<RowBuilder<'a>>,
+}
+
+impl<'a> RowStream<'a> {
+    pub(crate) fn new(options: &'a ParseOptions) -> Result<Self, String> {
+        let rows = if options.has_header { None } else { Some(RowBuilder::new(None, options)?) };
+        Ok(Self { options, header_lines: Vec::new(), rows })
+    }
+
+    /// Feed the next record, returning its row or `None` for a header record
+    pub(crate) fn push(&mut self, record: Vec<String>, quoted: &[bool]) -> Result<Option<Value>, String> {
+        if let Some(rows) = &self.rows {
+            return Ok(Some(rows.build(&record, quoted)));
+        }
+
+        self.header_lines.push(record);
+        let count = self.options.header_rows.max(1);
+        if self.header_lines.len() == count {
+            let mut merged = merge_header_rows(&self.header_lines, count, self.options.header_separator);
+            self.rows = Some(RowBuilder::new(Some(merged.swap_remove(0)), self.options)?);
+        }
+        Ok(None)
+    }
+}
+
+/// Replace the first `count` rows with a single header of joined names
+///
+/// Empty cells in the upper rows inherit the previous label in their row,
+/// matching how spreadsheets export merged group cells; empty parts are
+/// left out of the joined name.
+fn merge_header_rows(lines: &[Vec<String>], count: usize, separator: char) -> Vec<Vec<String>> {
+    let count = count.min(lines.len());
+    let width = lines[..count].iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut labels: Vec<Vec<String>> = vec![Vec::with_capacity(count); width];
+    for (row_index, row) in lines[..count].iter().enumerate() {
+        let mut group = "";
+        for (col, column_labels) in labels.iter_mut().enumerate() {
+            let mut cell = row.get(col).map(String::as_str).unwrap_or("");
+            if row_index + 1 < count {
+                if cell.is_empty() {
+                    cell = group;
+                } else {
+                    group = cell;
                 }
-                _ => current_field.push(ch),
+            }
+            if !cell.is_empty() {
+                column_labels.push(cell.to_string());
             }
         }
     }
 
-    // Handle last field and line
-    if !current_field.is_empty() || !current_line.is_empty() {
-        current_line.push(current_field);
-        if !current_line.is_empty() {
-            lines.push(current_line);
+    let header = labels
+        .into_iter()
+        .map(|parts| parts.join(&separator.to_string()))
+        .collect();
+    std::iter::once(header)
+        .chain(lines[count..].iter().cloned())
+        .collect()
+}
+
+/// Resolve `select_columns` to header indices
+///
+/// Returns `None` when no projection is requested. Unknown columns are an
+/// error unless `skip_missing_columns` is set, in which case they are ignored.
+fn select_indices(header: &[String], options: &ParseOptions) -> Result<Option<Vec<usize>>, String> {
+    let Some(columns) = &options.select_columns else {
+        return Ok(None);
+    };
+
+    let mut indices = Vec::with_capacity(columns.len());
+    for column in columns {
+        match header.iter().position(|name| name == column) {
+            Some(i) => indices.push(i),
+            None if options.skip_missing_columns => {}
+            None => return Err(format!("Selected column not found: {}", column)),
         }
     }
 
-    if lines.is_empty() {
-        return Ok("[]".to_string());
-    }
+    Ok(Some(indices))
+}
 
-    // Convert to JSON
-    let result = if has_header {
-        // Array of objects
-        let header = &lines[0];
-        let data_rows = &lines[1..];
+/// Build an object keyed by `key_column`, nesting the remaining columns
+fn records_to_keyed_object(
+    lines: &[Vec<String>],
+    key_column: &str,
+    cells: &CellConverter,
+) -> Result<Value, String> {
+    let options = cells.options;
+    let header = &lines[0];
+    let key_index = header
+        .iter()
+        .position(|name| name == key_column)
+        .ok_or_else(|| format!("Key column not found: {}", key_column))?;
 
-        let objects: Vec<Value> = data_rows
-            .iter()
-            .map(|row| {
-                let mut obj = serde_json::Map::new();
-                for (i, value) in row.iter().enumerate() {
-                    let key = header.get(i).map(|s| s.as_str()).unwrap_or("");
-                    let converted_value = if convert_types {
-                        convert_value(value)
-                    } else {
-                        Value::String(value.clone())
-                    };
-                    obj.insert(key.to_string(), converted_value);
-                }
-                Value::Object(obj)
-            })
-            .collect();
+    let mut result = serde_json::Map::new();
+    for (row_index, row) in lines[1..].iter().enumerate() {
+        let key = row.get(key_index).cloned().unwrap_or_default();
 
-        json!(objects)
-    } else {
-        // Array of arrays
-        let arrays: Vec<Value> = lines
-            .iter()
-            .map(|row| {
-                let arr: Vec<Value> = row
-                    .iter()
-                    .map(|v| {
-                        if convert_types {
-                            convert_value(v)
-                        } else {
-                            Value::String(v.clone())
-                        }
-                    })
-                    .collect();
-                json!(arr)
-            })
-            .collect();
+        let mut obj = serde_json::Map::new();
+        for (i, value) in row.iter().enumerate() {
+            if i == key_index {
+                continue;
+            }
+            let column = header.get(i).map(|s| s.as_str()).unwrap_or("");
+            obj.insert(column.to_string(), cells.convert(value, row_index + 1, i));
+        }
 
-        json!(arrays)
-    };
+        if options.duplicate_keys == DuplicateKeyPolicy::Error && result.contains_key(&key) {
+            return Err(format!("Duplicate key '{}' in row {}", key, row_index + 1));
+        }
+        result.insert(key, Value::Object(obj));
+    }
 
-    serde_json::to_string(&result).map_err(|e| format!("Serialization error: {}", e))
+    Ok(Value::Object(result))
 }
 
-fn convert_value(s: &str) -> Value {
-    // Try to parse as number
-    if let Ok(num) = s.parse::<i64>() {
-        return json!(num);
+/// Group rows sharing a `group_by` value into `{key, group columns..., items}`
+///
+/// Group columns are taken from the group's first row; every other column
+/// goes into the per-row `items` objects.
+fn records_to_groups(lines: &[Vec<String>], group_by: &str, cells: &CellConverter) -> Result<Value, String> {
+    let options = cells.options;
+    let header = &lines[0];
+    let column_index = |name: &str| {
+        header
+            .iter()
+            .position(|column| column == name)
+            .ok_or_else(|| format!("Group column not found: {}", name))
+    };
+    let key_index = column_index(group_by)?;
+    let mut group_indices = vec![key_index];
+    for name in &options.group_columns {
+        let index = column_index(name)?;
+        if !group_indices.contains(&index) {
+            group_indices.push(index);
+        }
     }
-    if let Ok(num) = s.parse::<f64>() {
-        return json!(num);
+
+    let mut groups: Vec<(String, serde_json::Map<String, Value>, Vec<Value>)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (row_index, row) in lines[1..].iter().enumerate() {
+        let row_index = row_index + 1;
+        let key = row.get(key_index).cloned().unwrap_or_default();
+
+        let contiguous = groups.last().is_some_and(|(last, _, _)| *last == key);
+        let existing = if contiguous {
+            Some(groups.len() - 1)
+        } else if options.merge_groups {
+            positions.get(&key).copied()
+        } else {
+            None
+        };
+        let group = match existing {
+            Some(group) => group,
+            None => {
+                let mut fields = serde_json::Map::new();
+                for &i in &group_indices {
+                    let value = row.get(i).map_or(Value::Null, |v| cells.convert(v, row_index, i));
+                    fields.insert(header[i].clone(), value);
+                }
+                positions.insert(key.clone(), groups.len());
+                groups.push((key, fields, Vec::new()));
+                groups.len() - 1
+            }
+        };
+
+        let mut item = serde_json::Map::new();
+        for (i, value) in row.iter().enumerate() {
+            if group_indices.contains(&i) {
+                continue;
+            }
+            let column = header.get(i).map(|s| s.as_str()).unwrap_or("");
+            item.insert(column.to_string(), cells.convert(value, row_index, i));
+        }
+        groups[group].2.push(Value::Object(item));
     }
 
-    // Try to parse as boolean
-    match s {
-        "true" => return json!(true),
-        "false" => return json!(false),
-        _ => {}
+    let records: Vec<Value> = groups
+        .into_iter()
+        .map(|(_, mut fields, items)| {
+            fields.insert("items".to_string(), Value::Array(items));
+            Value::Object(fields)
+        })
+        .collect();
+    Ok(Value::Array(records))
+}
+
+pub(crate) fn convert_value(s: &str, options: &ParseOptions) -> Value {
+    if options.allow_underscore_digits && s.contains('_') {
+        if let Some(digits) = strip_digit_separators(s) {
+            if let Ok(num) = digits.parse::<i64>() {
+                return json!(num);
+            }
+            if let Ok(num) = digits.parse::<f64>() {
+                return json!(num);
+            }
+        }
+        return Value::String(s.to_string());
     }
 
-    // Empty string
-    if s.is_empty() {
-        return Value::String(String::new());
+    // Numbers and booleans as JSON spells them; CSV has no null literal, so
+    // `null` stays text (see `null_values`)
+    match classify_scalar(s) {
+        ScalarKind::Integer => {
+            if let Ok(num) = s.parse::<i64>() {
+                return json!(num);
+            }
+        }
+        ScalarKind::Float => {
+            if let Ok(num) = s.parse::<f64>() {
+                return json!(num);
+            }
+        }
+        ScalarKind::Bool => return json!(s == "true"),
+        ScalarKind::Null | ScalarKind::String => {}
+    }
+    if options.parse_formatted_numbers {
+        if let Some(num) = formatted::parse_formatted_number(s, options) {
+            return num;
+        }
     }
 
     // Return as string
     Value::String(s.to_string())
 }
 
-fn stringify_csv_internal(
-    data: &Value,
-    delimiter: char,
-    include_header: bool,
-    quote_all: bool,
-) -> Result<String, String> {
+/// Remove `_` digit separators, or `None` if any `_` isn't between two digits
+fn strip_digit_separators(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let valid = bytes.iter().enumerate().all(|(i, &b)| {
+        b != b'_'
+            || (i > 0
+                && bytes[i - 1].is_ascii_digit()
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+    });
+    valid.then(|| s.replace('_', ""))
+}
+
+fn stringify_csv_internal(data: &Value, options: &StringifyOptions) -> Result<String, String> {
+    let delimiter = options.delimiter;
+    let include_header = options.include_header;
+    let terminator = if options.strict_rfc4180 { "\r\n" } else { "\n" };
     let array = data.as_array().ok_or("Input must be an array")?;
+    let array = prepare_rows(array, options)?;
+    let array = array.as_ref();
 
     if array.is_empty() {
         return Ok(String::new());
     }
 
     let mut output = String::new();
+    if options.bom {
+        output.push('\u{feff}');
+    }
 
     // Check if first element is an object or array
     if let Some(first) = array.first() {
@@ -237,25 +1426,19 @@ fn stringify_csv_internal(
                 return Ok(String::new());
             }
 
-            // Collect all unique keys
-            let mut columns: Vec<String> = Vec::new();
-            for obj in &objects {
-                for key in obj.keys() {
-                    if !columns.contains(key) {
-                        columns.push(key.clone());
-                    }
-                }
-            }
+            let columns = collect_columns(&objects);
 
             // Write header
             if include_header {
+                let types = options.typed_header.then(|| column_types(&columns, &objects, options));
                 for (i, col) in columns.iter().enumerate() {
                     if i > 0 {
                         output.push(delimiter);
                     }
-                    output.push_str(&serialize_field(col, delimiter, quote_all));
+                    let column_type = types.as_ref().map(|types| types[i]);
+                    output.push_str(&header_field(col, column_type, options));
                 }
-                output.push('\n');
+                output.push_str(terminator);
             }
 
             // Write data rows
@@ -265,10 +1448,9 @@ fn stringify_csv_internal(
                         output.push(delimiter);
                     }
                     let value = obj.get(col);
-                    let field = value_to_string(value);
-                    output.push_str(&serialize_field(&field, delimiter, quote_all));
+                    output.push_str(&serialize_value(value, options));
                 }
-                output.push('\n');
+                output.push_str(terminator);
             }
         } else if first.is_array() {
             // Array of arrays
@@ -278,10 +1460,9 @@ fn stringify_csv_internal(
                     if i > 0 {
                         output.push(delimiter);
                     }
-                    let field = value_to_string(Some(value));
-                    output.push_str(&serialize_field(&field, delimiter, quote_all));
+                    output.push_str(&serialize_value(Some(value), options));
                 }
-                output.push('\n');
+                output.push_str(terminator);
             }
         } else {
             return Err("Array elements must be objects or arrays".to_string());
@@ -289,42 +1470,447 @@ fn stringify_csv_internal(
     }
 
     // Remove trailing newline
-    if output.ends_with('\n') {
-        output.pop();
+    if output.ends_with(terminator) {
+        output.truncate(output.len() - terminator.len());
     }
 
     Ok(output)
 }
 
-fn value_to_string(value: Option<&Value>) -> String {
+/// Apply `melt` and `sort_by` to the rows about to be written
+pub(crate) fn prepare_rows<'a>(
+    array: &'a [Value],
+    options: &StringifyOptions,
+) -> Result<Cow<'a, [Value]>, String> {
+    let mut rows = match &options.melt {
+        Some(id_columns) => Cow::Owned(melt_objects(array, id_columns)?),
+        None => Cow::Borrowed(array),
+    };
+    if let Some((column, order)) = &options.sort_by {
+        sort_rows(rows.to_mut(), column, *order, options.nulls_first);
+    }
+    Ok(rows)
+}
+
+/// Stable sort of object rows by one column
+fn sort_rows(rows: &mut [Value], column: &str, order: SortOrder, nulls_first: bool) {
+    fn key<'a>(row: &'a Value, column: &str) -> Option<&'a Value> {
+        row.get(column).filter(|v| !v.is_null())
+    }
+    let numeric = rows.iter().filter_map(|row| key(row, column)).all(Value::is_number);
+
+    rows.sort_by(|a, b| {
+        let ordering = match (key(a, column), key(b, column)) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => return if nulls_first { Ordering::Less } else { Ordering::Greater },
+            (Some(_), None) => return if nulls_first { Ordering::Greater } else { Ordering::Less },
+            (Some(a), Some(b)) if numeric => {
+                let (a, b) = (a.as_f64().unwrap_or(0.0), b.as_f64().unwrap_or(0.0));
+                a.total_cmp(&b)
+            }
+            (Some(a), Some(b)) => sort_text(a).cmp(&sort_text(b)),
+        };
+        match order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    });
+}
+
+/// Text compared when sorting a non-numeric column
+fn sort_text(value: &Value) -> Cow<'_, str> {
+    match value {
+        Value::String(s) => Cow::Borrowed(s),
+        other => Cow::Owned(other.to_string()),
+    }
+}
+
+/// Declared or inferred type of each column, for `typed_header`
+pub(crate) fn column_types(
+    columns: &[String],
+    objects: &[&serde_json::Map<String, Value>],
+    options: &StringifyOptions,
+) -> Vec<ColumnType> {
+    columns
+        .iter()
+        .map(|col| match options.column_types.get(col) {
+            Some(&column_type) => column_type,
+            None => schema::infer_value_type(objects.iter().filter_map(|obj| obj.get(col))),
+        })
+        .collect()
+}
+
+/// Serialize one header name, annotated with its type when given
+pub(crate) fn header_field(
+    column: &str,
+    column_type: Option<ColumnType>,
+    options: &StringifyOptions,
+) -> String {
+    let name = options.header_transform.apply(column);
+    match column_type {
+        Some(column_type) => {
+            let type_name = options
+                .type_names
+                .get(&column_type)
+                .map_or(column_type.default_type_name(), String::as_str);
+            serialize_field(&format!("{} {}", name, type_name), options)
+        }
+        None => serialize_field(&name, options),
+    }
+}
+
+/// Collect all unique keys in first-seen order
+fn collect_columns(objects: &[&serde_json::Map<String, Value>]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for obj in objects {
+        for key in obj.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    columns
+}
+
+/// Reshape wide objects into long `id..., variable, value` objects
+///
+/// Variables are the union of non-id keys across all objects in first-seen
+/// order; objects missing a variable get an empty value for it.
+fn melt_objects(array: &[Value], id_columns: &[String]) -> Result<Vec<Value>, String> {
+    let objects: Vec<&serde_json::Map<String, Value>> = array
+        .iter()
+        .map(|v| v.as_object().ok_or("Melt requires an array of objects"))
+        .collect::<Result<_, _>>()?;
+
+    let mut variables: Vec<&String> = Vec::new();
+    for obj in &objects {
+        for key in obj.keys() {
+            if !id_columns.contains(key) && !variables.contains(&key) {
+                variables.push(key);
+            }
+        }
+    }
+
+    let mut rows = Vec::with_capacity(objects.len() * variables.len());
+    for obj in &objects {
+        for variable in &variables {
+            let mut row = serde_json::Map::new();
+            for id in id_columns {
+                row.insert(id.clone(), obj.get(id).cloned().unwrap_or(Value::Null));
+            }
+            row.insert("variable".to_string(), Value::String((*variable).clone()));
+            row.insert("value".to_string(), obj.get(*variable).cloned().unwrap_or(Value::Null));
+            rows.push(Value::Object(row));
+        }
+    }
+
+    Ok(rows)
+}
+
+fn value_to_string(value: Option<&Value>, options: &StringifyOptions) -> String {
     match value {
         Some(Value::String(s)) => s.clone(),
         Some(Value::Number(n)) => n.to_string(),
-        Some(Value::Bool(b)) => b.to_string(),
+        Some(Value::Bool(true)) => options.bool_output.0.clone(),
+        Some(Value::Bool(false)) => options.bool_output.1.clone(),
         Some(Value::Null) | None => String::new(),
-        Some(v) => v.to_string(),
+        Some(v) => encode_nested(v, options),
+    }
+}
+
+/// Render an array or object cell according to `nested_encoding`
+fn encode_nested(value: &Value, options: &StringifyOptions) -> String {
+    match options.nested_encoding {
+        NestedEncoding::Json => value.to_string(),
+        NestedEncoding::JsonPretty => {
+            serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+        }
+        NestedEncoding::SemicolonJoined => match value.as_array() {
+            Some(items) if items.iter().all(|v| !v.is_array() && !v.is_object()) => {
+                let mut joined = String::new();
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        joined.push(options.nested_join);
+                    }
+                    joined.push_str(&value_to_string(Some(item), options));
+                }
+                joined
+            }
+            _ => value.to_string(),
+        },
+        NestedEncoding::PythonRepr => {
+            let mut out = String::new();
+            push_python_repr(&mut out, value);
+            out
+        }
+    }
+}
+
+/// Append the Python literal form of a JSON value
+fn push_python_repr(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("None"),
+        Value::Bool(true) => out.push_str("True"),
+        Value::Bool(false) => out.push_str("False"),
+        Value::Number(n) => out.push_str(&n.to_string()),
+        Value::String(s) => {
+            // Like Python's repr: single quotes unless the text contains only single quotes
+            let quote = if s.contains('\'') && !s.contains('"') { '"' } else { '\'' };
+            out.push(quote);
+            for ch in s.chars() {
+                match ch {
+                    '\\' => out.push_str("\\\\"),
+                    '\n' => out.push_str("\\n"),
+                    '\r' => out.push_str("\\r"),
+                    '\t' => out.push_str("\\t"),
+                    c if c == quote => {
+                        out.push('\\');
+                        out.push(c);
+                    }
+                    c => out.push(c),
+                }
+            }
+            out.push(quote);
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                push_python_repr(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            out.push('{');
+            for (i, (key, item)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                push_python_repr(out, &Value::String(key.clone()));
+                out.push_str(": ");
+                push_python_repr(out, item);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// Convert a value to a serialized CSV field
+fn serialize_value(value: Option<&Value>, options: &StringifyOptions) -> String {
+    if matches!(value, Some(Value::Null) | None) && !options.null_value.is_empty() {
+        return options.null_value.clone();
+    }
+    let field = value_to_string(value, options);
+    let typed_string = options.quote_typed_strings
+        && matches!(value, Some(Value::String(s))
+            if !convert_value(s, &ParseOptions::default()).is_string());
+
+    if typed_string {
+        quote_field(&field)
+    } else {
+        serialize_field(&field, options)
     }
 }
 
-fn serialize_field(field: &str, delimiter: char, quote_all: bool) -> String {
-    let needs_quotes = quote_all
-        || field.contains(delimiter)
+/// Serialize a field, quoting it when it contains the delimiter, a quote or
+/// a control character (or per `quote_all`, `quote_commas_always` and strict
+/// RFC 4180 mode)
+fn serialize_field(field: &str, options: &StringifyOptions) -> String {
+    if options.backslash_escapes && !options.quote_all {
+        return escape_backslashes(field, options.delimiter);
+    }
+    let needs_quotes = options.quote_all
+        || field.contains(options.delimiter)
+        || (options.quote_commas_always && field.contains(','))
         || field.contains('"')
-        || field.contains('\n')
-        || field.contains('\r');
+        || field.contains(char::is_control)
+        || (options.strict_rfc4180 && is_ambiguous_field(field));
 
     if needs_quotes {
-        let escaped = field.replace('"', "\"\"");
-        format!("\"{}\"", escaped)
+        quote_field(field)
     } else {
         field.to_string()
     }
 }
 
+/// Escape a field PostgreSQL `COPY` style: backslash, delimiter, tab and
+/// line breaks become backslash sequences
+fn escape_backslashes(field: &str, delimiter: char) -> String {
+    let mut out = String::with_capacity(field.len());
+    for ch in field.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c == delimiter => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Wrap a field in quotes, doubling embedded quotes
+fn quote_field(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+/// Fields quoted only in strict RFC 4180 mode
+///
+/// - Leading or trailing whitespace (space or tab), which lenient readers trim
+/// - Number-like text that a naive reader would not round-trip verbatim:
+///   a leading zero before another digit (`007`), a leading `+`, a bare
+///   decimal point (`.5`, `5.`), or an exponent (`1e5`)
+fn is_ambiguous_field(field: &str) -> bool {
+    if field.starts_with([' ', '\t']) || field.ends_with([' ', '\t']) {
+        return true;
+    }
+
+    if field.parse::<f64>().is_err() || !field.bytes().any(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    let unsigned = field.strip_prefix('-').unwrap_or(field);
+    let bytes = unsigned.as_bytes();
+    field.starts_with('+')
+        || (bytes.len() > 1 && bytes[0] == b'0' && bytes[1].is_ascii_digit())
+        || unsigned.starts_with('.')
+        || unsigned.ends_with('.')
+        || unsigned.contains(['e', 'E'])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_group_by() {
+        let input = "order,customer,sku,qty\n1,ana,A,2\n1,ana,B,1\n2,bo,A,5\n2,bo,C,3\n1,ana,D,1";
+        let options = ParseOptions {
+            group_by: Some("order".to_string()),
+            group_columns: vec!["customer".to_string()],
+            ..ParseOptions::default()
+        };
+        let result: Value = serde_json::from_str(&parse_csv_with(input, &options).unwrap()).unwrap();
+        assert_eq!(
+            result,
+            json!([
+                {"order": 1, "customer": "ana", "items": [{"sku": "A", "qty": 2}, {"sku": "B", "qty": 1}]},
+                {"order": 2, "customer": "bo", "items": [{"sku": "A", "qty": 5}, {"sku": "C", "qty": 3}]},
+                {"order": 1, "customer": "ana", "items": [{"sku": "D", "qty": 1}]}
+            ])
+        );
+
+        let merged = ParseOptions { merge_groups: true, ..options };
+        let result: Value = serde_json::from_str(&parse_csv_with(input, &merged).unwrap()).unwrap();
+        assert_eq!(result.as_array().unwrap().len(), 2);
+        assert_eq!(result[0]["items"][2], json!({"sku": "D", "qty": 1}));
+    }
+
+    #[test]
+    fn test_parse_formatted_numbers() {
+        let options = ParseOptions {
+            delimiter: ';',
+            parse_formatted_numbers: true,
+            ..ParseOptions::default()
+        };
+        let input = "price;share;views;note\n$1,234.50;45%;1.2K;12 items";
+        assert_eq!(
+            parse_csv_with(input, &options).unwrap(),
+            r#"[{"price":1234.5,"share":0.45,"views":1200,"note":"12 items"}]"#
+        );
+    }
+
+    #[test]
+    fn test_csv_headers() {
+        let input = "id;\"full\nname\";\"say \"\"hi\"\"\"\r\n1;\"never closed";
+        assert_eq!(records_end(input, 1), Some(27));
+        assert_eq!(records_end(input, 2), None);
+        assert_eq!(records_end("a\r\nb\r\nc", 2), Some(4));
+        assert_eq!(csv_headers(input, ';').unwrap(), ["id", "full\nname", "say \"hi\""]);
+        assert_eq!(csv_headers("a,b", ',').unwrap(), ["a", "b"]);
+        assert!(csv_headers("", ',').unwrap().is_empty());
+        assert!(csv_headers("a,\"b\nc", ',').is_err());
+    }
+
+    #[test]
+    fn test_parse_with_spans() {
+        let input = "id,note\r\n1,\"a, \"\"b\"\"\"\n22,caf\u{e9}";
+        let rows = parse_csv_with_spans(input, &ParseOptions::default());
+        let cells: Vec<Vec<(&str, &str)>> = rows
+            .iter()
+            .map(|row| row.iter().map(|(value, span)| (value.as_str(), span.slice(input))).collect())
+            .collect();
+        assert_eq!(
+            cells,
+            vec![
+                vec![("id", "id"), ("note", "note")],
+                vec![("1", "1"), ("a, \"b\"", "\"a, \"\"b\"\"\"")],
+                vec![("22", "22"), ("caf\u{e9}", "caf\u{e9}")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_field_wrapper() {
+        let options = ParseOptions {
+            has_header: false,
+            field_wrapper: Some(('{', '}')),
+            ..ParseOptions::default()
+        };
+        let records = split_records("1,{a, b},x{y}\n2,{p {q, r}},\"s,t\"", &options);
+        assert_eq!(
+            records,
+            vec![vec!["1", "a, b", "x{y}"], vec!["2", "p {q, r}", "s,t"]]
+        );
+    }
+
+    #[test]
+    fn test_trailing_quote_runs() {
+        let options = ParseOptions { has_header: false, ..ParseOptions::default() };
+        let cases = [
+            ("\"a\"\"", r#"[["a\""]]"#),
+            ("\"a\"\"\"", r#"[["a\""]]"#),
+            ("\"\"\"a\"\"\"", r#"[["\"a\""]]"#),
+            ("\"she said \"\"hi\"\"\",x", r#"[["she said \"hi\"","x"]]"#),
+            ("x,\"a\"\"\"\"", r#"[["x","a\"\""]]"#),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_csv_with(input, &options).unwrap(), expected, "{}", input);
+            let lenient: Value =
+                serde_json::from_str(&parse_csv_lenient_with(input, &options).unwrap()).unwrap();
+            assert_eq!(lenient["rows"].to_string(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_sniff_has_header() {
+        assert!(sniff_has_header("name,age,active\nAlice,30,true\nBob,25,false\n", None));
+        assert!(!sniff_has_header("1,2.5,3\n4,5.5,6\n", None));
+        assert!(sniff_has_header("id;label\n1;a\n2;b", Some(';')));
+        assert!(!sniff_has_header("Alice,30\nBob,25", None));
+        assert!(sniff_has_header("a,b\nc,d\n", None));
+
+        // Rows after the sample don't affect the guess
+        let input = format!("id,7\n{}x,y\n", "1,2\r\n".repeat(20));
+        assert!(sniff_has_header(&input, None));
+    }
+
+    #[test]
+    fn test_multi_row_header() {
+        let options = ParseOptions { header_rows: 2, ..ParseOptions::default() };
+        let input = "id,Q1,,Q2,\n,rev,cost,rev,cost\n7,10,4,12,5";
+        assert_eq!(
+            parse_csv_with(input, &options).unwrap(),
+            r#"[{"id":7,"Q1.rev":10,"Q1.cost":4,"Q2.rev":12,"Q2.cost":5}]"#
+        );
+    }
+
     #[test]
     fn test_parse_simple_csv() {
         let csv = "name,age\nAlice,30\nBob,25";
@@ -333,6 +1919,17 @@ mod tests {
         assert!(result.contains("30"));
     }
 
+    #[test]
+    fn test_parse_json_number_literals_only() {
+        // Number-like text JSON rejects stays a string
+        let csv = "v\n007\n1.\n.5\n+1\n-0\n1e3\n0.25";
+        let result = parse_csv_with(csv, &ParseOptions::default()).unwrap();
+        assert_eq!(
+            result,
+            r#"[{"v":"007"},{"v":"1."},{"v":".5"},{"v":"+1"},{"v":0},{"v":1000.0},{"v":0.25}]"#
+        );
+    }
+
     #[test]
     fn test_parse_quoted_fields() {
         let csv = "name,address\nAlice,\"123 Main St, NYC\"";
@@ -340,6 +1937,147 @@ mod tests {
         assert!(result.contains("123 Main St, NYC"));
     }
 
+    #[test]
+    fn test_lenient_embedded_quotes() {
+        let csv = "size,desc\nM,6\" nails";
+        let options = ParseOptions { lenient_embedded_quotes: true, ..Default::default() };
+        let result = parse_csv_with(csv, &options).unwrap();
+        assert_eq!(result, r#"[{"size":"M","desc":"6\" nails"}]"#);
+
+        let options: ParseOptions = serde_json::from_str(r#"{"lenientEmbeddedQuotes":true}"#).unwrap();
+        assert!(options.lenient_embedded_quotes);
+        assert_eq!(options.delimiter, ',');
+    }
+
+    #[test]
+    fn test_lenient_doubled_quotes() {
+        let options = ParseOptions {
+            has_header: false,
+            lenient_doubled_quotes: true,
+            ..Default::default()
+        };
+        let result = parse_csv_with("a,ab\"\"cd,e\n\"\",\"x\"\"y\",z\"\"", &options).unwrap();
+        assert_eq!(result, r#"[["a","ab\"cd","e"],["","x\"y","z\""]]"#);
+    }
+
+    #[test]
+    fn test_header_order() {
+        let result = parse_csv_with("b,a\n1,2", &ParseOptions::default()).unwrap();
+        assert_eq!(result, r#"[{"b":1,"a":2}]"#);
+
+        let options = ParseOptions { key_column: Some("z".to_string()), ..Default::default() };
+        let result = parse_csv_with("z,y,x\nk,1,2", &options).unwrap();
+        assert_eq!(result, r#"{"k":{"y":1,"x":2}}"#);
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let input = "name,age\nAda,36\nBo,12\nCy,19\nDee,18";
+        let adults = |row: &Value| row["age"].as_i64().is_some_and(|age| age > 18);
+        let result = parse_csv_filter_with(input, &ParseOptions::default(), adults).unwrap();
+        assert_eq!(result, r#"[{"name":"Ada","age":36},{"name":"Cy","age":19}]"#);
+
+        let options = ParseOptions { has_header: false, ..Default::default() };
+        let result = parse_csv_filter_with("1,x\n2,y", &options, |row| row[0] == 2).unwrap();
+        assert_eq!(result, r#"[[2,"y"]]"#);
+
+        let options = ParseOptions {
+            null_values: vec!["NA".to_string()],
+            quoted_as_strings: true,
+            select_columns: Some(vec!["age".to_string(), "id".to_string()]),
+            header_rows: 2,
+            ..Default::default()
+        };
+        let input = ",,\nid,name,age\n\"7\",Ada,NA\n8,Bo,\"NA\"\n9,Cy,19";
+        let result = parse_csv_filter_with(input, &options, |row| row["id"] != "7").unwrap();
+        assert_eq!(result, r#"[{"age":"NA","id":8},{"age":19,"id":9}]"#);
+        assert_eq!(
+            result,
+            parse_csv_with(input, &options).unwrap().replace(r#"{"age":null,"id":"7"},"#, "")
+        );
+    }
+
+    #[test]
+    fn test_key_column() {
+        let csv = "id,name,age\na1,Alice,30\nb2,Bob,25";
+        let options = ParseOptions { key_column: Some("id".to_string()), ..Default::default() };
+        let result = parse_csv_with(csv, &options).unwrap();
+        assert_eq!(
+            result,
+            r#"{"a1":{"name":"Alice","age":30},"b2":{"name":"Bob","age":25}}"#
+        );
+
+        let missing = ParseOptions { key_column: Some("nope".to_string()), ..Default::default() };
+        assert!(parse_csv_with(csv, &missing).is_err());
+
+        let dup = "id,v\nx,1\nx,2";
+        assert_eq!(parse_csv_with(dup, &options).unwrap(), r#"{"x":{"v":2}}"#);
+        let strict = ParseOptions { duplicate_keys: DuplicateKeyPolicy::Error, ..options };
+        assert!(parse_csv_with(dup, &strict).is_err());
+    }
+
+    #[test]
+    fn test_select_columns() {
+        let csv = "a,b,c,d\n1,2,3,4\n5,6,7,8";
+        let options = ParseOptions {
+            select_columns: Some(vec!["d".to_string(), "b".to_string()]),
+            ..Default::default()
+        };
+        let result = parse_csv_with(csv, &options).unwrap();
+        assert_eq!(result, r#"[{"d":4,"b":2},{"d":8,"b":6}]"#);
+
+        let missing = ParseOptions {
+            select_columns: Some(vec!["a".to_string(), "z".to_string()]),
+            ..Default::default()
+        };
+        assert!(parse_csv_with(csv, &missing).is_err());
+        let skip = ParseOptions { skip_missing_columns: true, ..missing };
+        assert_eq!(parse_csv_with(csv, &skip).unwrap(), r#"[{"a":1},{"a":5}]"#);
+    }
+
+    #[test]
+    fn test_collapse_delimiters() {
+        let options = ParseOptions {
+            delimiter: ' ',
+            has_header: false,
+            collapse_delimiters: true,
+            ..ParseOptions::default()
+        };
+        let result = parse_csv_with("a    b   c\n  x \"\"  z  \n", &options).unwrap();
+        assert_eq!(result, r#"[["a","b","c"],["x","","z"]]"#);
+    }
+
+    #[test]
+    fn test_underscore_digits() {
+        let options = ParseOptions {
+            has_header: false,
+            allow_underscore_digits: true,
+            ..ParseOptions::default()
+        };
+        let result = parse_csv_with("1_000,1__0,_5,5_,-2_500.5,a_b", &options).unwrap();
+        assert_eq!(result, r#"[[1000,"1__0","_5","5_",-2500.5,"a_b"]]"#);
+
+        let result = parse_csv_with("1_000", &ParseOptions { has_header: false, ..ParseOptions::default() });
+        assert_eq!(result.unwrap(), r#"[["1_000"]]"#);
+    }
+
+    #[test]
+    fn test_parse_with_transform() {
+        let input = "name,email\nAlice,ALICE@Example.com\nBob,bob@example.com";
+        let result = parse_csv_transform_with(input, &ParseOptions::default(), |column, value| {
+            if column == "name" {
+                value.to_uppercase()
+            } else {
+                value.to_lowercase()
+            }
+        })
+        .unwrap();
+        assert_eq!(
+            result,
+            r#"[{"name":"ALICE","email":"alice@example.com"},{"name":"BOB","email":"bob@example.com"}]"#
+        );
+    }
+
     #[test]
     fn test_stringify_simple() {
         let json = r#"[{"name":"Alice","age":30}]"#;
@@ -347,4 +2085,168 @@ mod tests {
         assert!(result.contains("name,age"));
         assert!(result.contains("Alice,30"));
     }
+
+    #[test]
+    fn test_stringify_header_transform() {
+        let data = json!([{"first_name": "Ada", "age": 36}]);
+        let options = StringifyOptions {
+            header_transform: HeaderTransform::Title,
+            ..StringifyOptions::default()
+        };
+        assert_eq!(stringify_csv_with(&data, &options).unwrap(), "First Name,Age\nAda,36");
+
+        let options = StringifyOptions {
+            header_transform: HeaderTransform::Upper,
+            ..StringifyOptions::default()
+        };
+        assert_eq!(stringify_csv_with(&data, &options).unwrap(), "FIRST_NAME,AGE\nAda,36");
+    }
+
+    #[test]
+    fn test_control_characters_round_trip() {
+        let data = json!([{"raw": "a\tb\u{0B}c\u{0}d", "plain": "x"}]);
+        let csv = stringify_csv_with(&data, &StringifyOptions::default()).unwrap();
+        assert_eq!(csv, "raw,plain\n\"a\tb\u{0B}c\u{0}d\",x");
+
+        let options = ParseOptions { convert_types: false, ..ParseOptions::default() };
+        let parsed: Value = serde_json::from_str(&parse_csv_with(&csv, &options).unwrap()).unwrap();
+        assert_eq!(parsed, data);
+    }
+
+    #[test]
+    fn test_typed_header() {
+        let data = json!([{"name": "Alice", "age": 30, "score": 1.5}, {"name": "Bob", "age": null, "score": 2}]);
+        let options = StringifyOptions { typed_header: true, ..StringifyOptions::default() };
+        assert_eq!(
+            stringify_csv_with(&data, &options).unwrap(),
+            "name String,age Int64,score Float64\nAlice,30,1.5\nBob,,2"
+        );
+
+        let options = StringifyOptions {
+            column_types: [("age".to_string(), ColumnType::Number)].into(),
+            type_names: [(ColumnType::String, "Utf8".to_string())].into(),
+            ..options
+        };
+        let csv = stringify_csv_with(&data, &options).unwrap();
+        assert_eq!(csv.lines().next(), Some("name Utf8,age Float64,score Float64"));
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let data = json!([
+            {"name": "b", "age": 30},
+            {"name": "a", "age": 9},
+            {"name": "c"},
+            {"name": "d", "age": 100}
+        ]);
+        let ascending = StringifyOptions {
+            sort_by: Some(("age".to_string(), SortOrder::Asc)),
+            ..StringifyOptions::default()
+        };
+        assert_eq!(stringify_csv_with(&data, &ascending).unwrap(), "name,age\na,9\nb,30\nd,100\nc,");
+
+        let descending = StringifyOptions {
+            sort_by: Some(("age".to_string(), SortOrder::Desc)),
+            nulls_first: true,
+            ..StringifyOptions::default()
+        };
+        assert_eq!(stringify_csv_with(&data, &descending).unwrap(), "name,age\nc,\nd,100\nb,30\na,9");
+    }
+
+    #[test]
+    fn test_commas_with_other_delimiters() {
+        let data = json!([{"name": "Doe, Jane", "tags": "a|b"}]);
+        let tsv = StringifyOptions { delimiter: '\t', ..StringifyOptions::default() };
+        assert_eq!(stringify_csv_with(&data, &tsv).unwrap(), "name\ttags\nDoe, Jane\ta|b");
+
+        let pipe = StringifyOptions { delimiter: '|', quote_commas_always: true, ..StringifyOptions::default() };
+        assert_eq!(stringify_csv_with(&data, &pipe).unwrap(), "name|tags\n\"Doe, Jane\"|\"a|b\"");
+    }
+
+    #[test]
+    fn test_typed_strings_round_trip() {
+        let data = json!([{"code": "42", "flag": "true", "n": 42, "s": "x"}]);
+        let options = StringifyOptions { quote_typed_strings: true, ..StringifyOptions::default() };
+        let csv = stringify_csv_with(&data, &options).unwrap();
+        assert_eq!(csv, "code,flag,n,s\n\"42\",\"true\",42,x");
+
+        let options = ParseOptions { quoted_as_strings: true, ..ParseOptions::default() };
+        let parsed: Value = serde_json::from_str(&parse_csv_with(&csv, &options).unwrap()).unwrap();
+        assert_eq!(parsed, data);
+
+        let plain: Value =
+            serde_json::from_str(&parse_csv_with(&csv, &ParseOptions::default()).unwrap()).unwrap();
+        assert_eq!(plain[0]["code"], 42);
+    }
+
+    #[test]
+    fn test_bool_output() {
+        let data = json!([{"id": 1, "active": true}, {"id": 2, "active": false}]);
+        let stringify = |yes: &str, no: &str| {
+            let options = StringifyOptions {
+                bool_output: (yes.to_string(), no.to_string()),
+                ..Default::default()
+            };
+            stringify_csv_with(&data, &options).unwrap()
+        };
+
+        assert_eq!(stringify("1", "0"), "id,active\n1,1\n2,0");
+        assert_eq!(stringify("Yes", "No"), "id,active\n1,Yes\n2,No");
+        let options: StringifyOptions = serde_json::from_str(r#"{"boolOutput": ["TRUE", "FALSE"]}"#).unwrap();
+        assert_eq!(stringify_csv_with(&json!([[true]]), &options).unwrap(), "TRUE");
+    }
+
+    #[test]
+    fn test_stringify_strict_rfc4180() {
+        let data = json!([
+            {"a": " leading", "b": "007", "c": 1.5, "d": "+3", "e": "plain"},
+            {"a": "x", "b": "1e5", "c": 2, "d": ".5", "e": "trailing "}
+        ]);
+        let options = StringifyOptions { strict_rfc4180: true, ..Default::default() };
+        let result = stringify_csv_with(&data, &options).unwrap();
+        assert_eq!(
+            result,
+            "a,b,c,d,e\r\n\" leading\",\"007\",1.5,\"+3\",plain\r\nx,\"1e5\",2,\".5\",\"trailing \""
+        );
+
+        let lenient = stringify_csv_with(&data, &StringifyOptions::default()).unwrap();
+        assert!(lenient.starts_with("a,b,c,d,e\n leading,007,"));
+    }
+
+    #[test]
+    fn test_stringify_nested_encoding() {
+        let data = json!([{"id": 1, "tags": ["a", "b"], "meta": [{"k": null}]}]);
+
+        let joined = StringifyOptions {
+            nested_encoding: NestedEncoding::SemicolonJoined,
+            ..StringifyOptions::default()
+        };
+        assert_eq!(
+            stringify_csv_with(&data, &joined).unwrap(),
+            "id,tags,meta\n1,a;b,\"[{\"\"k\"\":null}]\""
+        );
+
+        let pipe = StringifyOptions { nested_join: '|', ..joined };
+        assert_eq!(stringify_csv_with(&data, &pipe).unwrap().lines().nth(1), Some("1,a|b,\"[{\"\"k\"\":null}]\""));
+
+        let python = StringifyOptions {
+            nested_encoding: NestedEncoding::PythonRepr,
+            ..StringifyOptions::default()
+        };
+        assert_eq!(
+            stringify_csv_with(&data, &python).unwrap(),
+            "id,tags,meta\n1,\"['a', 'b']\",[{'k': None}]"
+        );
+    }
+
+    #[test]
+    fn test_stringify_melt() {
+        let data = json!([{"id": 1, "a": 10, "b": 20}, {"id": 2, "a": 30, "c": 40}]);
+        let options = StringifyOptions { melt: Some(vec!["id".to_string()]), ..Default::default() };
+        let result = stringify_csv_with(&data, &options).unwrap();
+        assert_eq!(
+            result,
+            "id,variable,value\n1,a,10\n1,b,20\n1,c,\n2,a,30\n2,b,\n2,c,40"
+        );
+    }
 }
@@ -0,0 +1,196 @@
+//! Per-column statistics for data profiling
+//!
+//! Each cell is converted once with the regular type conversion and folded
+//! into its column's running statistics, so profiling needs no second pass
+//! over the parsed rows.
+
+use std::collections::HashSet;
+
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::schema::ColumnType;
+use crate::{convert_value, split_records, ParseOptions};
+
+/// Distinct values tracked per column before the count stops being exact
+const DISTINCT_CAP: usize = 10_000;
+
+/// Profile each column of a CSV with a header row
+///
+/// Returns `{"rows": n, "columns": [...]}` where each column has `name`,
+/// `type`, `count` (non-empty cells), `nullCount` (empty or missing cells),
+/// `min`, `max`, `distinct` and `distinctExact`. Numeric columns compare by
+/// value, all others lexicographically.
+///
+/// # Arguments
+/// * `input` - CSV string to profile
+/// * `delimiter` - Field delimiter (default: ',')
+#[wasm_bindgen]
+pub fn parse_csv_profile(input: &str, delimiter: Option<char>) -> Result<String, JsValue> {
+    let defaults = ParseOptions::default();
+    let options = ParseOptions {
+        delimiter: delimiter.unwrap_or(defaults.delimiter),
+        ..defaults
+    };
+
+    parse_csv_profile_with(input, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Profile CSV columns with explicit options
+pub fn parse_csv_profile_with(input: &str, options: &ParseOptions) -> Result<String, String> {
+    let mut records = split_records(input, options).into_iter();
+    let header = records.next().unwrap_or_default();
+    let mut columns: Vec<ColumnProfile> = header.iter().map(|_| ColumnProfile::default()).collect();
+
+    let mut rows = 0;
+    for record in records {
+        rows += 1;
+        for (col, profile) in columns.iter_mut().enumerate() {
+            let cell = record.get(col).map(String::as_str).unwrap_or("");
+            profile.observe(cell, options);
+        }
+    }
+
+    let columns: Vec<Value> = header
+        .iter()
+        .zip(columns)
+        .map(|(name, profile)| profile.into_json(name))
+        .collect();
+    let result = json!({ "rows": rows, "columns": columns });
+    serde_json::to_string(&result).map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Running statistics for one column
+#[derive(Debug)]
+struct ColumnProfile {
+    count: usize,
+    null_count: usize,
+    /// Whether every non-empty cell so far converted to an integer, a number
+    /// or a boolean
+    all_integer: bool,
+    all_number: bool,
+    all_boolean: bool,
+    /// Extremes among numeric cells, with the converted value to report
+    min_number: Option<(f64, Value)>,
+    max_number: Option<(f64, Value)>,
+    /// Lexicographic extremes among all non-empty cells
+    min_text: Option<String>,
+    max_text: Option<String>,
+    distinct: HashSet<String>,
+    distinct_exact: bool,
+}
+
+impl Default for ColumnProfile {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            null_count: 0,
+            all_integer: true,
+            all_number: true,
+            all_boolean: true,
+            min_number: None,
+            max_number: None,
+            min_text: None,
+            max_text: None,
+            distinct: HashSet::new(),
+            distinct_exact: true,
+        }
+    }
+}
+
+impl ColumnProfile {
+    fn observe(&mut self, cell: &str, options: &ParseOptions) {
+        if cell.is_empty() {
+            self.null_count += 1;
+            return;
+        }
+        self.count += 1;
+
+        let value = convert_value(cell, options);
+        self.all_integer &= value.is_i64() || value.is_u64();
+        self.all_number &= value.is_number();
+        self.all_boolean &= value.is_boolean();
+        if let Some(n) = value.as_f64() {
+            if self.min_number.as_ref().is_none_or(|(min, _)| n < *min) {
+                self.min_number = Some((n, value.clone()));
+            }
+            if self.max_number.as_ref().is_none_or(|(max, _)| n > *max) {
+                self.max_number = Some((n, value));
+            }
+        }
+
+        if self.min_text.as_deref().is_none_or(|min| cell < min) {
+            self.min_text = Some(cell.to_string());
+        }
+        if self.max_text.as_deref().is_none_or(|max| cell > max) {
+            self.max_text = Some(cell.to_string());
+        }
+
+        if self.distinct.len() < DISTINCT_CAP {
+            self.distinct.insert(cell.to_string());
+        } else if !self.distinct.contains(cell) {
+            self.distinct_exact = false;
+        }
+    }
+
+    /// Narrowest type all non-empty cells share; empty columns are strings
+    fn column_type(&self) -> ColumnType {
+        if self.count == 0 {
+            ColumnType::String
+        } else if self.all_integer {
+            ColumnType::Integer
+        } else if self.all_number {
+            ColumnType::Number
+        } else if self.all_boolean {
+            ColumnType::Boolean
+        } else {
+            ColumnType::String
+        }
+    }
+
+    fn into_json(self, name: &str) -> Value {
+        let column_type = self.column_type();
+        let (min, max) = match column_type {
+            ColumnType::Integer | ColumnType::Number => (
+                self.min_number.map(|(_, value)| value),
+                self.max_number.map(|(_, value)| value),
+            ),
+            _ => (self.min_text.map(Value::String), self.max_text.map(Value::String)),
+        };
+        json!({
+            "name": name,
+            "type": column_type,
+            "count": self.count,
+            "nullCount": self.null_count,
+            "min": min,
+            "max": max,
+            "distinct": self.distinct.len(),
+            "distinctExact": self.distinct_exact,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile() {
+        let input = "age,city\n30,Oslo\n4.5,Bergen\n,Oslo\n-2\n";
+        let profile: Value =
+            serde_json::from_str(&parse_csv_profile_with(input, &ParseOptions::default()).unwrap())
+                .unwrap();
+        assert_eq!(
+            profile,
+            json!({
+                "rows": 4,
+                "columns": [
+                    {"name": "age", "type": "number", "count": 3, "nullCount": 1,
+                     "min": -2, "max": 30, "distinct": 3, "distinctExact": true},
+                    {"name": "city", "type": "string", "count": 3, "nullCount": 1,
+                     "min": "Bergen", "max": "Oslo", "distinct": 2, "distinctExact": true},
+                ]
+            })
+        );
+    }
+}
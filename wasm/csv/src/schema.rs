@@ -0,0 +1,289 @@
+//! Schema-validated CSV parsing
+//!
+//! Instead of guessing types, every declared column is checked against its
+//! declared type. Cells that don't match are reported or nulled according to
+//! `SchemaOptions::invalid_values`.
+
+use molt_core::{classify_scalar, ScalarKind};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::{split_records, ParseOptions};
+
+/// Parse CSV, validating each declared column against its type
+///
+/// Returns `{"rows": [...], "errors": [{"row": n, "column": "...", "message": "..."}]}`
+/// where `row` is the 1-based data row (the header is not counted).
+///
+/// # Arguments
+/// * `input` - CSV string to parse (must have a header row)
+/// * `schema_json` - Column declarations, e.g. `[{"name":"age","type":"integer"}]`
+/// * `delimiter` - Field delimiter (default: ',')
+/// * `null_invalid` - Silently null mismatched cells instead of reporting them (default: false)
+#[wasm_bindgen]
+pub fn parse_csv_with_schema(
+    input: &str,
+    schema_json: &str,
+    delimiter: Option<char>,
+    null_invalid: Option<bool>,
+) -> Result<String, JsValue> {
+    let schema: Vec<ColumnSchema> = serde_json::from_str(schema_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid schema: {}", e)))?;
+
+    let defaults = SchemaOptions::default();
+    let options = SchemaOptions {
+        delimiter: delimiter.unwrap_or(defaults.delimiter),
+        invalid_values: if null_invalid.unwrap_or(false) {
+            InvalidValuePolicy::Null
+        } else {
+            InvalidValuePolicy::Error
+        },
+    };
+
+    parse_csv_with_schema_with(input, &schema, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Declared column name and type
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub column_type: ColumnType,
+}
+
+/// Value type of a schema column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ColumnType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+impl ColumnType {
+    /// ClickHouse-style type name used in typed CSV headers
+    pub fn default_type_name(self) -> &'static str {
+        match self {
+            ColumnType::String => "String",
+            ColumnType::Integer => "Int64",
+            ColumnType::Number => "Float64",
+            ColumnType::Boolean => "Bool",
+        }
+    }
+}
+
+/// Handling of cells that don't match their declared type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InvalidValuePolicy {
+    /// Null the cell and record an error (default)
+    #[default]
+    Error,
+    /// Null the cell without recording anything
+    Null,
+}
+
+/// Options for schema-validated parsing
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SchemaOptions {
+    /// Field delimiter (default: ',')
+    pub delimiter: char,
+    /// How mismatched cells are handled (default: error)
+    pub invalid_values: InvalidValuePolicy,
+}
+
+impl Default for SchemaOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            invalid_values: InvalidValuePolicy::Error,
+        }
+    }
+}
+
+/// Parse CSV against a schema with explicit options
+///
+/// Columns not declared in the schema are kept as strings; declared columns
+/// missing from the header are an error. Empty cells become `null`.
+pub fn parse_csv_with_schema_with(
+    input: &str,
+    schema: &[ColumnSchema],
+    options: &SchemaOptions,
+) -> Result<String, String> {
+    let parse_options = ParseOptions {
+        delimiter: options.delimiter,
+        ..ParseOptions::default()
+    };
+    let records = split_records(input, &parse_options);
+    let Some((header, data_rows)) = records.split_first() else {
+        return Ok(json!({ "rows": [], "errors": [] }).to_string());
+    };
+
+    for column in schema {
+        if !header.contains(&column.name) {
+            return Err(format!("Column not found: {}", column.name));
+        }
+    }
+
+    let types: Vec<Option<ColumnType>> = header
+        .iter()
+        .map(|name| schema.iter().find(|c| &c.name == name).map(|c| c.column_type))
+        .collect();
+
+    let mut rows = Vec::with_capacity(data_rows.len());
+    let mut errors = Vec::new();
+    for (row_idx, record) in data_rows.iter().enumerate() {
+        let mut obj = Map::new();
+        for (col_idx, name) in header.iter().enumerate() {
+            let raw = record.get(col_idx).map(String::as_str).unwrap_or("");
+            let value = match types[col_idx] {
+                None => Value::String(raw.to_string()),
+                Some(column_type) => match typed_value(raw, column_type) {
+                    Some(value) => value,
+                    None => {
+                        if options.invalid_values == InvalidValuePolicy::Error {
+                            errors.push(json!({
+                                "row": row_idx + 1,
+                                "column": name,
+                                "message": format!("Expected {:?}, found {:?}", column_type, raw),
+                            }));
+                        }
+                        Value::Null
+                    }
+                },
+            };
+            obj.insert(name.clone(), value);
+        }
+        rows.push(Value::Object(obj));
+    }
+
+    let result = json!({ "rows": rows, "errors": errors });
+    serde_json::to_string(&result).map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Infer the narrowest type that every non-empty cell of a column satisfies
+///
+/// Columns with no non-empty cells are strings.
+pub(crate) fn infer_column_type<'a>(cells: impl Iterator<Item = &'a str> + Clone) -> ColumnType {
+    let mut filled = cells.filter(|cell| !cell.trim().is_empty()).peekable();
+    if filled.peek().is_none() {
+        return ColumnType::String;
+    }
+
+    [ColumnType::Integer, ColumnType::Number, ColumnType::Boolean]
+        .into_iter()
+        .find(|&column_type| filled.clone().all(|cell| typed_value(cell, column_type).is_some()))
+        .unwrap_or(ColumnType::String)
+}
+
+/// Narrowest type shared by all non-null JSON values of a column
+pub(crate) fn infer_value_type<'a>(values: impl Iterator<Item = &'a Value> + Clone) -> ColumnType {
+    let mut filled = values.filter(|value| !value.is_null()).peekable();
+    if filled.peek().is_none() {
+        return ColumnType::String;
+    }
+
+    if filled.clone().all(|value| value.is_i64() || value.is_u64()) {
+        ColumnType::Integer
+    } else if filled.clone().all(Value::is_number) {
+        ColumnType::Number
+    } else if filled.all(|value| value.is_boolean()) {
+        ColumnType::Boolean
+    } else {
+        ColumnType::String
+    }
+}
+
+/// Convert a cell to its declared type, or `None` if it doesn't match
+///
+/// Numbers must be JSON number literals, as in `parse_csv`, so `007`, `+1`,
+/// `.5` and `1.` match neither `Integer` nor `Number`.
+pub(crate) fn typed_value(raw: &str, column_type: ColumnType) -> Option<Value> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() && column_type != ColumnType::String {
+        return Some(Value::Null);
+    }
+
+    match column_type {
+        ColumnType::String => Some(Value::String(raw.to_string())),
+        ColumnType::Integer => match classify_scalar(trimmed) {
+            ScalarKind::Integer => trimmed.parse::<i64>().ok().map(Value::from),
+            _ => None,
+        },
+        ColumnType::Number => match classify_scalar(trimmed) {
+            ScalarKind::Integer | ScalarKind::Float => trimmed
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number),
+            _ => None,
+        },
+        ColumnType::Boolean => match trimmed.to_ascii_lowercase().as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Vec<ColumnSchema> {
+        serde_json::from_str(r#"[{"name":"name","type":"string"},{"name":"age","type":"integer"}]"#)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_schema_mismatch() {
+        let input = "name,age,note\nAlice,30,x\nBob,abc,y";
+        let result: Value = serde_json::from_str(
+            &parse_csv_with_schema_with(input, &schema(), &SchemaOptions::default()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            result["rows"],
+            json!([
+                {"name": "Alice", "age": 30, "note": "x"},
+                {"name": "Bob", "age": null, "note": "y"}
+            ])
+        );
+        assert_eq!(result["errors"][0]["row"], 2);
+        assert_eq!(result["errors"][0]["column"], "age");
+
+        let null_options = SchemaOptions {
+            invalid_values: InvalidValuePolicy::Null,
+            ..SchemaOptions::default()
+        };
+        let result: Value = serde_json::from_str(
+            &parse_csv_with_schema_with(input, &schema(), &null_options).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(result["errors"], json!([]));
+        assert_eq!(result["rows"][1]["age"], Value::Null);
+    }
+
+    #[test]
+    fn test_schema_missing_column() {
+        let err = parse_csv_with_schema_with("name\nAlice", &schema(), &SchemaOptions::default());
+        assert_eq!(err.unwrap_err(), "Column not found: age");
+    }
+
+    #[test]
+    fn test_json_number_literals_only() {
+        for cell in ["007", "+1", ".5", "1."] {
+            assert_eq!(typed_value(cell, ColumnType::Integer), None, "{}", cell);
+            assert_eq!(typed_value(cell, ColumnType::Number), None, "{}", cell);
+        }
+        assert_eq!(typed_value(" 12 ", ColumnType::Integer), Some(json!(12)));
+        assert_eq!(typed_value("1e3", ColumnType::Number), Some(json!(1000.0)));
+
+        assert_eq!(infer_column_type(["1", "007"].into_iter()), ColumnType::String);
+        assert_eq!(infer_column_type(["1", "2"].into_iter()), ColumnType::Integer);
+        assert_eq!(infer_column_type(["1", "-0.5"].into_iter()), ColumnType::Number);
+    }
+}
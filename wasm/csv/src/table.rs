@@ -0,0 +1,210 @@
+//! Aligned text tables for console display
+//!
+//! Cells are rendered like CSV fields and padded to the widest cell in
+//! their column, measured in approximate terminal display width.
+
+use serde::Deserialize;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::{collect_columns, value_to_string, StringifyOptions};
+
+/// Render a JSON array as an aligned text table
+///
+/// # Arguments
+/// * `json_input` - JSON array of objects (or arrays) to render
+/// * `delimiter` - Column separator (default: '|')
+/// * `markdown` - Emit a GitHub-flavored markdown table (default: false)
+#[wasm_bindgen]
+pub fn stringify_table(
+    json_input: &str,
+    delimiter: Option<char>,
+    markdown: Option<bool>,
+) -> Result<String, JsValue> {
+    let defaults = TableOptions::default();
+    let options = TableOptions {
+        delimiter: delimiter.unwrap_or(defaults.delimiter),
+        markdown: markdown.unwrap_or(defaults.markdown),
+    };
+
+    let data: Value = serde_json::from_str(json_input)
+        .map_err(|e| JsValue::from_str(&format!("Invalid JSON: {}", e)))?;
+
+    stringify_table_with(&data, &options).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Options for table rendering
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TableOptions {
+    /// Column separator; ignored in markdown mode (default: '|')
+    pub delimiter: char,
+    /// Emit a markdown table with a `---` row under the header (default: false)
+    pub markdown: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: '|',
+            markdown: false,
+        }
+    }
+}
+
+/// Render a JSON array as an aligned text table with explicit options
+///
+/// Arrays of objects get a header row from the union of their keys. For
+/// arrays of arrays the first row is the header in markdown mode and an
+/// ordinary row otherwise.
+pub fn stringify_table_with(data: &Value, options: &TableOptions) -> Result<String, String> {
+    let array = data.as_array().ok_or("Input must be an array")?;
+    let cell_options = StringifyOptions::default();
+    let cell = |value: Option<&Value>| {
+        let text = value_to_string(value, &cell_options).replace(['\r', '\n'], " ");
+        if options.markdown {
+            text.replace('|', "\\|")
+        } else {
+            text
+        }
+    };
+
+    let (header, rows): (Option<Vec<String>>, Vec<Vec<String>>) = match array.first() {
+        None => return Ok(String::new()),
+        Some(Value::Object(_)) => {
+            let objects: Vec<_> = array.iter().filter_map(|v| v.as_object()).collect();
+            let columns = collect_columns(&objects);
+            let rows = objects
+                .iter()
+                .map(|obj| columns.iter().map(|col| cell(obj.get(col))).collect())
+                .collect();
+            let header = columns.iter().map(|col| cell(Some(&Value::String(col.clone())))).collect();
+            (Some(header), rows)
+        }
+        Some(Value::Array(_)) => {
+            let mut rows = array
+                .iter()
+                .map(|row| {
+                    let values = row.as_array().ok_or("Row must be an array")?;
+                    Ok(values.iter().map(|v| cell(Some(v))).collect())
+                })
+                .collect::<Result<Vec<Vec<String>>, String>>()?;
+            let header = if options.markdown { Some(rows.remove(0)) } else { None };
+            (header, rows)
+        }
+        Some(_) => return Err("Array elements must be objects or arrays".to_string()),
+    };
+
+    let column_count = header
+        .iter()
+        .chain(rows.iter())
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+    let mut widths = vec![if options.markdown { 3 } else { 0 }; column_count];
+    for row in header.iter().chain(rows.iter()) {
+        for (i, text) in row.iter().enumerate() {
+            widths[i] = widths[i].max(display_width(text));
+        }
+    }
+
+    let mut lines = Vec::with_capacity(rows.len() + 2);
+    if let Some(header) = &header {
+        lines.push(render_row(header, &widths, options));
+        if options.markdown {
+            let dashes: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+            lines.push(render_row(&dashes, &widths, options));
+        }
+    }
+    for row in &rows {
+        lines.push(render_row(row, &widths, options));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Pad and join one row of cells
+fn render_row(cells: &[String], widths: &[usize], options: &TableOptions) -> String {
+    let mut line = String::new();
+    if options.markdown {
+        line.push_str("| ");
+    }
+
+    for (i, &width) in widths.iter().enumerate() {
+        if i > 0 {
+            if options.markdown {
+                line.push_str(" | ");
+            } else {
+                line.push(' ');
+                line.push(options.delimiter);
+                line.push(' ');
+            }
+        }
+        let text = cells.get(i).map(String::as_str).unwrap_or("");
+        line.push_str(text);
+        for _ in display_width(text)..width {
+            line.push(' ');
+        }
+    }
+
+    if options.markdown {
+        line.push_str(" |");
+        line
+    } else {
+        line.trim_end().to_string()
+    }
+}
+
+/// Approximate terminal display width
+///
+/// East Asian wide and fullwidth characters and most emoji count as two
+/// columns, combining marks and zero-width characters as none.
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F => 0,
+            0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_table_aligned() {
+        let data = json!([{"name": "Alice", "age": 30}, {"name": "Bob", "age": 7}]);
+        let table = stringify_table_with(&data, &TableOptions::default()).unwrap();
+        assert_eq!(table, "name  | age\nAlice | 30\nBob   | 7");
+
+        let markdown = TableOptions { markdown: true, ..TableOptions::default() };
+        let table = stringify_table_with(&data, &markdown).unwrap();
+        assert_eq!(
+            table,
+            "| name  | age |\n| ----- | --- |\n| Alice | 30  |\n| Bob   | 7   |"
+        );
+    }
+
+    #[test]
+    fn test_table_wide_characters() {
+        let data = json!([{"city": "東京", "n": 1}, {"city": "Oslo", "n": 2}]);
+        let table = stringify_table_with(&data, &TableOptions::default()).unwrap();
+        assert_eq!(table, "city | n\n東京 | 1\nOslo | 2");
+    }
+}
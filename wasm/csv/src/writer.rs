@@ -0,0 +1,248 @@
+//! Incremental CSV serialization
+//!
+//! `CsvWriter` appends records one at a time instead of requiring the whole
+//! array up front. Because earlier rows can't be rewritten, the column set is
+//! fixed by the first object record (or declared when the writer is created).
+//!
+//! `flush` hands buffered rows to a callback once they pass a byte threshold,
+//! so huge outputs can be sent to a size-limited sink (such as multipart
+//! upload parts) without holding the whole CSV in memory.
+
+use serde_json::Value;
+
+use crate::{
+    collect_columns, column_types, header_field, prepare_rows, serialize_value, StringifyOptions,
+};
+
+/// Streaming CSV writer producing the same text as `stringify_csv_with`
+#[derive(Debug, Clone)]
+pub struct CsvWriter {
+    options: StringifyOptions,
+    columns: Option<Vec<String>>,
+    output: String,
+    records: usize,
+    /// Whether any line has been written, including lines already flushed
+    started: bool,
+}
+
+impl CsvWriter {
+    /// Create a writer whose columns are taken from the first object record
+    pub fn new(options: StringifyOptions) -> Self {
+        Self {
+            options,
+            columns: None,
+            output: String::new(),
+            records: 0,
+            started: false,
+        }
+    }
+
+    /// Create a writer with a pre-declared column list
+    pub fn with_columns(columns: Vec<String>, options: StringifyOptions) -> Self {
+        Self {
+            columns: Some(columns),
+            ..Self::new(options)
+        }
+    }
+
+    /// Append one record (an object, or an array of values)
+    ///
+    /// Objects with keys outside the fixed column set are rejected.
+    pub fn write_record(&mut self, record: &Value) -> Result<(), String> {
+        if self.options.melt.is_some() {
+            return Err("melt is not supported by CsvWriter".to_string());
+        }
+
+        match record {
+            Value::Object(obj) => {
+                let columns = match &self.columns {
+                    Some(columns) => {
+                        if let Some(key) = obj.keys().find(|k| !columns.contains(k)) {
+                            return Err(format!("Unknown column: {}", key));
+                        }
+                        columns.clone()
+                    }
+                    None => {
+                        let columns: Vec<String> = obj.keys().cloned().collect();
+                        self.columns = Some(columns.clone());
+                        columns
+                    }
+                };
+
+                if self.records == 0 && self.options.include_header {
+                    // Only the first record is known here, so `typed_header`
+                    // infers from it unless `column_types` declares the type
+                    let types = self
+                        .options
+                        .typed_header
+                        .then(|| column_types(&columns, &[obj], &self.options));
+                    let header: Vec<String> = columns
+                        .iter()
+                        .enumerate()
+                        .map(|(i, col)| header_field(col, types.as_ref().map(|t| t[i]), &self.options))
+                        .collect();
+                    self.push_row(header.into_iter());
+                }
+                let fields: Vec<String> = columns
+                    .iter()
+                    .map(|col| serialize_value(obj.get(col), &self.options))
+                    .collect();
+                self.push_row(fields.into_iter());
+            }
+            Value::Array(values) => {
+                let fields: Vec<String> = values
+                    .iter()
+                    .map(|value| serialize_value(Some(value), &self.options))
+                    .collect();
+                self.push_row(fields.into_iter());
+            }
+            _ => return Err("Records must be objects or arrays".to_string()),
+        }
+
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Number of records written so far (the header is not counted)
+    pub fn records_written(&self) -> usize {
+        self.records
+    }
+
+    /// Consume the writer and return the CSV text not yet flushed
+    pub fn finish(self) -> String {
+        self.output
+    }
+
+    /// Pass the buffered text to `on_chunk` once it reaches `threshold` bytes
+    ///
+    /// Only whole rows are buffered, so chunks never split a row. The line
+    /// terminator between two chunks starts the later chunk, which keeps the
+    /// concatenated chunks identical to the unflushed output.
+    pub fn flush(&mut self, threshold: usize, mut on_chunk: impl FnMut(&str)) {
+        if !self.output.is_empty() && self.output.len() >= threshold {
+            on_chunk(&self.output);
+            self.output.clear();
+        }
+    }
+
+    /// Write one line of serialized fields, separating it from the previous
+    /// line with the terminator
+    fn push_row(&mut self, fields: impl Iterator<Item = String>) {
+        if self.started {
+            self.output
+                .push_str(if self.options.strict_rfc4180 { "\r\n" } else { "\n" });
+        } else if self.options.bom {
+            self.output.push('\u{feff}');
+        }
+        self.started = true;
+        for (i, field) in fields.enumerate() {
+            if i > 0 {
+                self.output.push(self.options.delimiter);
+            }
+            self.output.push_str(&field);
+        }
+    }
+}
+
+/// Stringify a JSON array to CSV, emitting the text in chunks
+///
+/// Produces the same text as `stringify_csv_with`, passed to `on_chunk` in
+/// pieces of whole rows. A chunk is emitted as soon as the buffered rows
+/// reach `chunk_size` bytes, so no chunk exceeds it by more than one row.
+pub fn stringify_csv_chunked(
+    data: &Value,
+    options: &StringifyOptions,
+    chunk_size: usize,
+    mut on_chunk: impl FnMut(&str),
+) -> Result<(), String> {
+    let array = data.as_array().ok_or("Input must be an array")?;
+    let array = prepare_rows(array, options)?;
+    let array = array.as_ref();
+    let mut options = StringifyOptions { melt: None, sort_by: None, ..options.clone() };
+
+    let mut writer = match array.first() {
+        Some(Value::Object(_)) => {
+            let objects: Vec<_> = array.iter().filter_map(Value::as_object).collect();
+            let columns = collect_columns(&objects);
+            if options.typed_header {
+                // Infer from every row, as the batch stringifier does
+                let types = column_types(&columns, &objects, &options);
+                options.column_types = columns.iter().cloned().zip(types).collect();
+            }
+            CsvWriter::with_columns(columns, options)
+        }
+        _ => CsvWriter::new(options),
+    };
+    let objects_only = array.first().is_some_and(Value::is_object);
+    if !objects_only && array.first().is_some_and(|first| !first.is_array()) {
+        return Err("Array elements must be objects or arrays".to_string());
+    }
+    for record in array {
+        if objects_only && !record.is_object() {
+            continue;
+        }
+        if !objects_only && !record.is_array() {
+            return Err("Row must be an array".to_string());
+        }
+        writer.write_record(record)?;
+        writer.flush(chunk_size, &mut on_chunk);
+    }
+
+    let rest = writer.finish();
+    if !rest.is_empty() {
+        on_chunk(&rest);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stringify_csv_with;
+    use serde_json::json;
+
+    #[test]
+    fn test_writer_matches_batch() {
+        let records = json!([
+            {"name": "Alice", "age": 30, "note": "a,b"},
+            {"name": "Bob", "age": 25, "note": null},
+            {"name": "Carol", "age": 41, "note": "say \"hi\""}
+        ]);
+        let options = StringifyOptions::default();
+
+        let mut writer = CsvWriter::new(options.clone());
+        for record in records.as_array().unwrap() {
+            writer.write_record(record).unwrap();
+        }
+        assert_eq!(writer.records_written(), 3);
+        assert_eq!(writer.finish(), stringify_csv_with(&records, &options).unwrap());
+    }
+
+    #[test]
+    fn test_chunked_output() {
+        let records: Vec<Value> = (0..500)
+            .map(|i| json!({"id": i, "name": format!("user {}", i), "active": i % 3 == 0}))
+            .collect();
+        let data = Value::Array(records);
+        let options = StringifyOptions::default();
+        let expected = stringify_csv_with(&data, &options).unwrap();
+        let longest_row = expected.lines().map(|line| line.len() + 1).max().unwrap();
+
+        let mut chunks = Vec::new();
+        stringify_csv_chunked(&data, &options, 1024, |chunk| chunks.push(chunk.to_string()))
+            .unwrap();
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| chunk.len() < 1024 + longest_row));
+        assert!(chunks[1..].iter().all(|chunk| chunk.starts_with('\n')));
+        assert_eq!(chunks.concat(), expected);
+    }
+
+    #[test]
+    fn test_writer_fixed_columns() {
+        let mut writer =
+            CsvWriter::with_columns(vec!["a".into(), "b".into()], StringifyOptions::default());
+        writer.write_record(&json!({"b": 2})).unwrap();
+        assert!(writer.write_record(&json!({"c": 3})).is_err());
+        assert_eq!(writer.finish(), "a,b\n,2");
+    }
+}
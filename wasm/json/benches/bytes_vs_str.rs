@@ -0,0 +1,39 @@
+//! Byte input against `&str` input on a 20 MB ASCII document
+//!
+//! The bytes path validates only the text that ends up in tokens, where the
+//! `&str` path starts from input already known to be UTF-8. Natively that
+//! is all that differs; from JS the `&str` entry points also pay for passing
+//! a string across the boundary, which this doesn't measure. Run with
+//! `cargo bench -p molt-json-wasm --bench bytes_vs_str`.
+
+use std::time::{Duration, Instant};
+
+use molt_json_wasm::{clean_bytes_with_options, clean_with_options, CleanOptions};
+
+/// Fastest of five runs of `f`, with its last result
+fn best_of<T>(mut f: impl FnMut() -> T) -> (T, Duration) {
+    let mut best = Duration::MAX;
+    let mut result = None;
+    for _ in 0..5 {
+        let started = Instant::now();
+        result = Some(f());
+        best = best.min(started.elapsed());
+    }
+    (result.unwrap(), best)
+}
+
+fn main() {
+    let record = "{id: 12345, name: 'alice', tags: ['a', 'b',], score: 98.5, note: \"x\\ty\",}, // r\n";
+    let mut input = String::from("[");
+    while input.len() < 20 << 20 {
+        input.push_str(record);
+    }
+    input.push(']');
+    let options = CleanOptions::default();
+
+    let (from_str, str_time) = best_of(|| clean_with_options(&input, &options).unwrap());
+    let (from_bytes, bytes_time) = best_of(|| clean_bytes_with_options(input.as_bytes(), &options).unwrap());
+    assert_eq!(from_str.as_bytes(), from_bytes);
+
+    println!("20 MB ASCII: &str {:?}, bytes {:?}", str_time, bytes_time);
+}
@@ -0,0 +1,71 @@
+//! Heap accounting shared by the allocation benchmarks
+//!
+//! Each benchmark is its own binary, so installing a counting global
+//! allocator here affects only the benchmarks that declare `mod common`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAlloc;
+
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn track(allocated: usize, freed: usize) {
+    let live = LIVE.fetch_add(allocated, Ordering::Relaxed) + allocated;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+    LIVE.fetch_sub(freed, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            track(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        track(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            track(new_size, layout.size());
+        }
+        new
+    }
+}
+
+/// Heap use of one measured call
+#[derive(Debug, Clone, Copy)]
+pub struct Usage {
+    /// Allocations and reallocations made
+    pub allocations: usize,
+    /// Most bytes live at once, above what was live before the call
+    pub peak_bytes: usize,
+}
+
+/// Run `f`, returning its result and the heap it used
+///
+/// The benchmarks are single-threaded, so everything counted is `f`'s.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let base = LIVE.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    let usage = Usage {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        peak_bytes: PEAK.load(Ordering::Relaxed) - base,
+    };
+    (result, usage)
+}
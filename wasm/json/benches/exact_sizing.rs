@@ -0,0 +1,40 @@
+//! Output allocations with and without `exact_output_size` on a 20 MB document
+//!
+//! The estimate reserves from the token lengths, over-reserving for most
+//! documents and reallocating for the rest; the dry run costs a second walk
+//! over the tokens but reserves the output exactly. Run with
+//! `cargo bench -p molt-json-wasm --bench exact_sizing`.
+
+mod common;
+
+use std::time::Instant;
+
+use molt_json_wasm::{clean_with_options, CleanOptions};
+
+fn main() {
+    let record = "{id: 12345, name: 'alice \\'al\\'', tags: ['a', 'b',], score: 98.5, note: null,}, ";
+    let mut input = String::from("[");
+    while input.len() < 20 << 20 {
+        input.push_str(record);
+    }
+    input.push(']');
+
+    let mut outputs = Vec::new();
+    for exact_output_size in [false, true] {
+        let options = CleanOptions { exact_output_size, ..CleanOptions::default() };
+        let started = Instant::now();
+        let (output, usage) = common::measure(|| clean_with_options(&input, &options).unwrap());
+        let elapsed = started.elapsed();
+        println!(
+            "exact_output_size {:<5}: {} allocations, {} bytes peak, {} bytes reserved for {}, {:?}",
+            exact_output_size,
+            usage.allocations,
+            usage.peak_bytes,
+            output.capacity(),
+            output.len(),
+            elapsed
+        );
+        outputs.push(output);
+    }
+    assert_eq!(outputs[0], outputs[1]);
+}
@@ -0,0 +1,32 @@
+//! Reused handle against per-call cleaning over 10k small documents
+//!
+//! Run with `cargo bench -p molt-json-wasm --bench handle_reuse`.
+
+use std::time::Instant;
+
+use molt_json_wasm::{clean_with_options, CleanOptions, JsonCleanerHandle};
+
+fn main() {
+    let inputs: Vec<String> = (0..10_000)
+        .map(|i| format!("{{id: {}, name: 'user {}', tags: ['a', 'b',], active: true,}}", i, i))
+        .collect();
+    let options = CleanOptions::default();
+
+    let started = Instant::now();
+    let mut per_call_bytes = 0;
+    for input in &inputs {
+        per_call_bytes += clean_with_options(input, &options).unwrap().len();
+    }
+    let per_call = started.elapsed();
+
+    let started = Instant::now();
+    let mut handle = JsonCleanerHandle::new();
+    let mut reused_bytes = 0;
+    for input in &inputs {
+        reused_bytes += handle.clean_str(input).unwrap().len();
+    }
+    let reused = started.elapsed();
+    assert_eq!(per_call_bytes, reused_bytes);
+
+    println!("10k documents: per call {:?}, reused handle {:?}", per_call, reused);
+}
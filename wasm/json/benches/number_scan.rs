@@ -0,0 +1,38 @@
+//! SIMD against scalar number run scanning over 100k floats
+//!
+//! Off wasm32 both scans are the scalar loop, so the comparison is only
+//! meaningful on a wasm32 runner with simd128, e.g.
+//! `RUSTFLAGS="-C target-feature=+simd128" cargo bench -p molt-json-wasm
+//! --features bench-internals --bench number_scan --target wasm32-wasip1`
+//! with wasmtime set as `CARGO_TARGET_WASM32_WASIP1_RUNNER`.
+
+use std::time::Instant;
+
+use molt_json_wasm::{number_run_length, number_run_length_scalar};
+
+fn main() {
+    let input: String = (0..100_000)
+        .map(|i| format!("{}.{:06}e-3, ", i * 7919, i))
+        .collect();
+    let bytes = input.as_bytes();
+    let starts: Vec<usize> = std::iter::once(0)
+        .chain(bytes.iter().enumerate().filter(|&(_, &b)| b == b' ').map(|(i, _)| i + 1))
+        .filter(|&start| start < bytes.len())
+        .collect();
+
+    let time = |scan: fn(&[u8], usize, usize) -> usize| {
+        let started = Instant::now();
+        let mut total = 0;
+        for _ in 0..20 {
+            for &start in &starts {
+                total += scan(bytes, start, bytes.len());
+            }
+        }
+        (total, started.elapsed())
+    };
+    let (simd_total, simd) = time(number_run_length);
+    let (scalar_total, scalar) = time(number_run_length_scalar);
+    assert_eq!(simd_total, scalar_total);
+
+    println!("100k floats x20: SIMD {:?}, scalar {:?}", simd, scalar);
+}
@@ -0,0 +1,31 @@
+//! Peak heap use of stage 1 on a 50 MB document
+//!
+//! The eager index stores a position and a type per structural character;
+//! the scanner allocates nothing. Run with
+//! `cargo bench -p molt-json-wasm --bench structural_memory`.
+
+mod common;
+
+use molt_json_wasm::{StructuralIndex, StructuralScanner};
+
+fn main() {
+    let record = br#"{"id": 12345, "name": "alice", "tags": ['a', 'b'], "score": 98.5},"#;
+    let mut input = b"[".to_vec();
+    while input.len() < 50 << 20 {
+        input.extend_from_slice(record);
+    }
+    input.push(b']');
+
+    let (indexed, eager) = common::measure(|| StructuralIndex::build(&input).len());
+    let (scanned, streaming) = common::measure(|| StructuralScanner::new(&input).count());
+    assert_eq!(indexed, scanned);
+
+    println!(
+        "StructuralIndex:   {:>11} bytes peak, {} allocations",
+        eager.peak_bytes, eager.allocations
+    );
+    println!(
+        "StructuralScanner: {:>11} bytes peak, {} allocations",
+        streaming.peak_bytes, streaming.allocations
+    );
+}
@@ -0,0 +1,106 @@
+//! Columnar transposition of record arrays
+//!
+//! `[{"a":1,"b":2},{"a":3,"b":4}]` and `{"a":[1,3],"b":[2,4]}` hold the same
+//! table; the columnar form repeats each key once. Only arrays whose objects
+//! all have the same keys are transposed, so the conversion is reversible.
+
+use serde_json::{Map, Value};
+use wasm_bindgen::prelude::*;
+
+/// Turn columnar JSON (an object of equal-length arrays) back into records
+///
+/// Input that isn't in columnar form is returned unchanged.
+#[wasm_bindgen]
+pub fn decolumnar_json(input: &str) -> Result<String, JsValue> {
+    decolumnar_str(input).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Native form of `decolumnar_json`
+pub fn decolumnar_str(input: &str) -> Result<String, String> {
+    let value: Value = serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {}", e))?;
+    serde_json::to_string(&from_columnar(value)).map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Transpose an array of objects that all share the same keys
+///
+/// The key order of the first object is kept. Empty arrays, arrays with a
+/// non-object element and objects with differing keys are returned as-is.
+pub fn to_columnar(value: Value) -> Value {
+    let Value::Array(records) = value else {
+        return value;
+    };
+    let uniform = match records.first() {
+        Some(Value::Object(first)) => records.iter().all(|record| {
+            record
+                .as_object()
+                .is_some_and(|obj| obj.len() == first.len() && first.keys().all(|k| obj.contains_key(k)))
+        }),
+        _ => false,
+    };
+    if !uniform {
+        return Value::Array(records);
+    }
+
+    let mut columns: Map<String, Value> = records[0]
+        .as_object()
+        .into_iter()
+        .flat_map(|first| first.keys())
+        .map(|key| (key.clone(), Value::Array(Vec::with_capacity(records.len()))))
+        .collect();
+    for record in records {
+        if let Value::Object(obj) = record {
+            for (key, value) in obj {
+                if let Some(Value::Array(column)) = columns.get_mut(&key) {
+                    column.push(value);
+                }
+            }
+        }
+    }
+    Value::Object(columns)
+}
+
+/// Inverse of `to_columnar`: split an object of equal-length arrays into records
+///
+/// Objects that are empty, have a non-array value or arrays of different
+/// lengths are returned as-is.
+pub fn from_columnar(value: Value) -> Value {
+    let Value::Object(columns) = value else {
+        return value;
+    };
+    let rows = match columns.values().next() {
+        Some(Value::Array(first)) => first.len(),
+        _ => return Value::Object(columns),
+    };
+    if !columns.values().all(|column| column.as_array().is_some_and(|c| c.len() == rows)) {
+        return Value::Object(columns);
+    }
+
+    let mut records = vec![Map::new(); rows];
+    for (key, column) in columns {
+        if let Value::Array(values) = column {
+            for (record, value) in records.iter_mut().zip(values) {
+                record.insert(key.clone(), value);
+            }
+        }
+    }
+    Value::Array(records.into_iter().map(Value::Object).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_columnar_round_trip() {
+        let records = json!([{"a": 1, "b": 2}, {"b": 4, "a": 3}]);
+        let columnar = to_columnar(records);
+        assert_eq!(columnar, json!({"a": [1, 3], "b": [2, 4]}));
+        assert_eq!(from_columnar(columnar), json!([{"a": 1, "b": 2}, {"a": 3, "b": 4}]));
+
+        for unchanged in [json!([{"a": 1}, {"a": 2, "b": 3}]), json!([{"a": 1}, 2]), json!([]), json!({"a": 1})] {
+            assert_eq!(to_columnar(unchanged.clone()), unchanged);
+        }
+        assert_eq!(from_columnar(json!({"a": [1], "b": [1, 2]})), json!({"a": [1], "b": [1, 2]}));
+    }
+}
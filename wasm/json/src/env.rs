@@ -0,0 +1,96 @@
+//! Environment-variable expansion in string values
+//!
+//! `$VAR` and `${VAR}` references are replaced from `CleanOptions::env_vars`
+//! and, with the `env` feature, from the process environment. WASM builds
+//! have no environment, so callers pass the values they want expanded.
+
+use std::borrow::Cow;
+
+use crate::options::{CleanOptions, UnsetEnvPolicy};
+
+/// Expand variable references in a raw (still escaped) string token value
+///
+/// Substituted values are escaped for the raw form, so a backslash in a
+/// variable stays a literal backslash. Returns the name of the first unset
+/// variable when `unset_env` is `Error`.
+pub(crate) fn expand_env<'a>(raw: &'a str, options: &CleanOptions) -> Result<Cow<'a, str>, String> {
+    if !raw.contains('$') {
+        return Ok(Cow::Borrowed(raw));
+    }
+
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(dollar) = rest.find('$') {
+        out.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+        let Some((name, len)) = reference(after) else {
+            out.push('$');
+            rest = after;
+            continue;
+        };
+
+        match lookup(name, options) {
+            Some(value) => out.push_str(&value.replace('\\', "\\\\")),
+            None if options.unset_env == UnsetEnvPolicy::Error => return Err(name.to_string()),
+            None => out.push_str(&rest[dollar..dollar + 1 + len]),
+        }
+        rest = &after[len..];
+    }
+    out.push_str(rest);
+    Ok(Cow::Owned(out))
+}
+
+/// Variable name referenced right after a `$` and the length of the
+/// reference (`NAME` or `{NAME}`), if there is one
+fn reference(after: &str) -> Option<(&str, usize)> {
+    if let Some(braced) = after.strip_prefix('{') {
+        let close = braced.find('}')?;
+        let name = &braced[..close];
+        return is_name(name).then_some((name, close + 2));
+    }
+    let len = after
+        .bytes()
+        .position(|b| !(b.is_ascii_alphanumeric() || b == b'_'))
+        .unwrap_or(after.len());
+    let name = &after[..len];
+    is_name(name).then_some((name, len))
+}
+
+/// Whether `name` is a variable name (`[A-Za-z_][A-Za-z0-9_]*`)
+fn is_name(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    bytes.next().is_some_and(|b| b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+fn lookup<'a>(name: &str, options: &'a CleanOptions) -> Option<Cow<'a, str>> {
+    if let Some(value) = options.env_vars.get(name) {
+        return Some(Cow::Borrowed(value));
+    }
+    #[cfg(feature = "env")]
+    if let Ok(value) = std::env::var(name) {
+        return Some(Cow::Owned(value));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_env() {
+        let options = CleanOptions {
+            expand_env: true,
+            env_vars: [("HOME".to_string(), "/home/ana".to_string()), ("DIR".to_string(), "C:\\x".to_string())]
+                .into(),
+            ..CleanOptions::default()
+        };
+        assert_eq!(expand_env("$HOME/data", &options).unwrap(), "/home/ana/data");
+        assert_eq!(expand_env("${DIR}_1 costs $5 $", &options).unwrap(), "C:\\\\x_1 costs $5 $");
+        assert_eq!(expand_env("$UNSET_X-${UNSET_X}", &options).unwrap(), "$UNSET_X-${UNSET_X}");
+
+        let strict = CleanOptions { unset_env: UnsetEnvPolicy::Error, ..options };
+        assert_eq!(expand_env("a $UNSET_X", &strict).unwrap_err(), "UNSET_X");
+    }
+}
@@ -0,0 +1,43 @@
+//! Gzip-compressed input for native callers (`gzip` feature)
+
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use molt_core::ParseError;
+
+use crate::{clean_with_options, CleanOptions};
+
+/// Leading bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Inflate gzip-compressed dirty JSON and clean it
+pub fn clean_dirty_json_gz(bytes: &[u8]) -> Result<String, ParseError> {
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Err(ParseError::new("Input is not gzip-compressed", 0));
+    }
+
+    let mut text = String::new();
+    GzDecoder::new(bytes)
+        .read_to_string(&mut text)
+        .map_err(|e| ParseError::new(format!("Gzip error: {}", e), 0))?;
+    clean_with_options(&text, &CleanOptions::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn test_clean_dirty_json_gz() {
+        let input = "{a: 1, b: [true,],}";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(input.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(clean_dirty_json_gz(&compressed).unwrap(), r#"{"a":1,"b":[true]}"#);
+        assert!(clean_dirty_json_gz(input.as_bytes()).is_err());
+    }
+}
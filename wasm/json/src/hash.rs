@@ -0,0 +1,122 @@
+//! Semantic hashing of dirty JSON for change detection
+//!
+//! The input is cleaned, parsed and written in a canonical form (keys sorted,
+//! no whitespace, numbers by value) before hashing, so formatting, key order
+//! and the dirty syntax the cleaner repairs don't change the hash.
+
+use molt_core::ParseError;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::clean_with_options;
+use crate::options::CleanOptions;
+
+/// Hash the meaning of dirty JSON as 16 hex digits; see `json_semantic_hash`
+#[wasm_bindgen]
+pub fn hash_dirty_json(input: &str) -> Result<String, JsValue> {
+    json_semantic_hash(input).map_err(|e| JsValue::from_str(&e.message))
+}
+
+/// 64-bit FNV-1a hash of the canonical form of `input`, as hex
+///
+/// Inputs that clean to equal values hash the same: `{a:1,b:2}` and
+/// `{"b": 2.0, "a": 1,}` collide by design. Not suitable where an attacker
+/// chooses inputs; use `json_semantic_sha256` (`sha256` feature) there.
+pub fn json_semantic_hash(input: &str) -> Result<String, ParseError> {
+    let canonical = canonical_json(input)?;
+    let hash = canonical.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    Ok(format!("{:016x}", hash))
+}
+
+/// SHA-256 of the canonical form of `input`, as hex
+#[cfg(feature = "sha256")]
+pub fn json_semantic_sha256(input: &str) -> Result<String, ParseError> {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(canonical_json(input)?.as_bytes());
+    Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Clean `input` and write it canonically
+fn canonical_json(input: &str) -> Result<String, ParseError> {
+    let cleaned = clean_with_options(input, &CleanOptions::default())?;
+    let value: Value = serde_json::from_str(&cleaned)
+        .map_err(|e| ParseError::new(format!("Cleaned output is not strict JSON: {}", e), 0))?;
+    let mut out = String::with_capacity(cleaned.len());
+    push_canonical(&mut out, &value);
+    Ok(out)
+}
+
+/// Append `value` with object keys sorted and numbers in canonical form
+///
+/// Integral numbers within `f64`'s exact range are written as integers, so
+/// `1`, `1.0` and `1e0` agree; other numbers use the shortest round-trip form.
+fn push_canonical(out: &mut String, value: &Value) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                push_canonical(out, value);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                push_canonical(out, item);
+            }
+            out.push(']');
+        }
+        Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+            (Some(i), _, _) => out.push_str(&i.to_string()),
+            (_, Some(u), _) => out.push_str(&u.to_string()),
+            (_, _, Some(f)) if f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 => {
+                out.push_str(&(f as i64).to_string());
+            }
+            (_, _, Some(f)) => out.push_str(&f.to_string()),
+            _ => out.push_str(&n.to_string()),
+        },
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semantic_hash() {
+        let hash = json_semantic_hash("{a:1,b:2}").unwrap();
+        assert_eq!(hash.len(), 16);
+        assert_eq!(json_semantic_hash("{b:2, a:1,}").unwrap(), hash);
+        assert_eq!(json_semantic_hash("// cfg\n{\"b\": 2.0, 'a': 1e0}").unwrap(), hash);
+        assert_ne!(json_semantic_hash("{a:1,b:3}").unwrap(), hash);
+        assert_ne!(json_semantic_hash("[1,2]").unwrap(), json_semantic_hash("[2,1]").unwrap());
+    }
+
+    #[cfg(feature = "sha256")]
+    #[test]
+    fn test_semantic_sha256() {
+        let hash = json_semantic_sha256("{a:1,b:2}").unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(json_semantic_sha256("{b:2, a:1,}").unwrap(), hash);
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let canonical = canonical_json("{z: [1.50, -0.0], a: {y: 'q', x: null}}").unwrap();
+        assert_eq!(canonical, r#"{"a":{"x":null,"y":"q"},"z":[1.5,0]}"#);
+    }
+}
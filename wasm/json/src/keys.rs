@@ -0,0 +1,96 @@
+//! Object key tracking for the reconstructor
+//!
+//! Token streams do not mark which strings are keys, so the reconstructor
+//! follows the container nesting to know when it is expecting a key
+//! (right after `{`, or after `,` inside an object).
+
+use std::borrow::Cow;
+
+use molt_core::*;
+use crate::options::KeyCase;
+
+/// Tracks whether the next token sits in object key position
+#[derive(Debug, Default)]
+pub struct KeyTracker {
+    /// Open containers: `true` for objects, `false` for arrays
+    stack: Vec<bool>,
+    expect_key: bool,
+}
+
+impl KeyTracker {
+    /// Whether the next token is an object key
+    #[inline]
+    pub fn is_key(&self) -> bool {
+        self.expect_key && self.in_object()
+    }
+
+    /// Whether the innermost open container is an object
+    #[inline]
+    pub fn in_object(&self) -> bool {
+        self.stack.last() == Some(&true)
+    }
+
+    /// Whether the innermost open container is an array
+    #[inline]
+    pub fn in_array(&self) -> bool {
+        self.stack.last() == Some(&false)
+    }
+
+    /// Advance past a token
+    pub fn observe(&mut self, token_type: TokenType) {
+        match token_type {
+            TokenType::LeftBrace => {
+                self.stack.push(true);
+                self.expect_key = true;
+            }
+            TokenType::LeftBracket => {
+                self.stack.push(false);
+                self.expect_key = false;
+            }
+            TokenType::RightBrace | TokenType::RightBracket => {
+                self.stack.pop();
+                self.expect_key = false;
+            }
+            TokenType::Comma => self.expect_key = self.in_object(),
+            _ => self.expect_key = false,
+        }
+    }
+}
+
+/// Apply the configured case to a key
+///
+/// Keys containing escape sequences are returned unchanged so that
+/// converting the case cannot corrupt an escape.
+pub fn apply_key_case(key: &str, case: KeyCase) -> Cow<'_, str> {
+    if key.contains('\\') {
+        return Cow::Borrowed(key);
+    }
+
+    match case {
+        KeyCase::AsIs => Cow::Borrowed(key),
+        KeyCase::Lower => Cow::Owned(key.to_lowercase()),
+        KeyCase::Upper => Cow::Owned(key.to_uppercase()),
+        KeyCase::Snake => Cow::Owned(to_snake_case(key)),
+        KeyCase::Camel => Cow::Owned(to_camel_case(key)),
+    }
+}
+
+/// Match a key against a pattern where `*` stands for any run of characters
+pub fn key_matches(pattern: &str, key: &str) -> bool {
+    let Some((first, rest)) = pattern.split_once('*') else {
+        return pattern == key;
+    };
+    let Some(mut remaining) = key.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let last = parts.pop().unwrap_or("");
+    for part in parts {
+        match remaining.find(part) {
+            Some(at) => remaining = &remaining[at + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() >= last.len() && remaining.ends_with(last)
+}
@@ -7,12 +7,51 @@
 //! - JavaScript-style comments (// and /* */)
 //! - Trailing commas in objects and arrays
 
+mod columnar;
+mod env;
+#[cfg(feature = "gzip")]
+mod gzip;
+mod hash;
+mod keys;
+mod lint;
+mod nested;
+mod number;
+mod options;
+mod output;
+mod pretty;
 mod simd;
 mod two_stage;
+#[cfg(feature = "unicode-normalize")]
+mod unicode;
+
+use std::borrow::Cow;
 
 use molt_core::*;
 use wasm_bindgen::prelude::*;
 
+pub use options::{
+    BigNumberMode, CleanOptions, Dialect, DropPolicy, HolePolicy, IdentifierPolicy, KeyCase,
+    KeySeparators, NestedJsonMode, OverflowPolicy, UnsetEnvPolicy,
+};
+#[cfg(feature = "gzip")]
+pub use gzip::clean_dirty_json_gz;
+pub use columnar::{decolumnar_str, from_columnar, to_columnar};
+pub use hash::json_semantic_hash;
+#[cfg(feature = "sha256")]
+pub use hash::json_semantic_sha256;
+pub use lint::{lint_json, Diagnostic, Severity};
+pub use pretty::{format_jsonc, format_preserving, IndentStyle};
+pub use simd::{
+    quick_scan, Edit, ScanResult, SimdStats, StructType, StructuralIndex, StructuralScanner,
+};
+#[cfg(feature = "bench-internals")]
+pub use simd::{number_run_length, number_run_length_scalar};
+#[cfg(feature = "unicode-normalize")]
+pub use unicode::NormForm;
+
+use keys::{apply_key_case, key_matches, KeyTracker};
+use output::{Output, SizeCounter, SliceOutput};
+
 /// High-performance dirty JSON cleaner
 ///
 /// This function takes malformed JSON and returns valid JSON.
@@ -23,6 +62,19 @@ pub fn clean_dirty_json(input: &str) -> Result<String, JsValue> {
         .map_err(|e| JsValue::from_str(&e.message))
 }
 
+/// Clean dirty JSON with an options object
+///
+/// `options_json` is a JSON object using camelCase field names, e.g.
+/// `{"keyCase": "snake", "commentStyles": ["doubleSlash", "hash"]}`.
+/// Unspecified fields use their defaults.
+#[wasm_bindgen]
+pub fn clean_dirty_json_with_options(input: &str, options_json: &str) -> Result<String, JsValue> {
+    let options: CleanOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid options: {}", e)))?;
+
+    clean_with_options(input, &options).map_err(|e| JsValue::from_str(&e.message))
+}
+
 /// SIMD-accelerated dirty JSON cleaner (v2)
 ///
 /// Uses two-stage parsing with SIMD structural indexing for better performance.
@@ -30,31 +82,418 @@ pub fn clean_dirty_json(input: &str) -> Result<String, JsValue> {
 /// Stage 2: Extract tokens from index (minimal branching)
 #[wasm_bindgen]
 pub fn clean_dirty_json_simd(input: &str) -> Result<String, JsValue> {
-    two_stage::parse_two_stage(input)
-        .map(|tokens| reconstruct_json(&tokens))
+    two_stage::parse_two_stage(input, &CleanOptions::default())
+        .and_then(|tokens| reconstruct_json(&tokens, &CleanOptions::default()))
         .map_err(|e| JsValue::from_str(&e.message))
 }
 
-fn clean_dirty_json_internal(input: &str) -> Result<String, ParseError> {
-    let tokens = tokenize(input)?;
-    let json = reconstruct_json(&tokens);
-    Ok(json)
+/// Clean dirty JSON via the SIMD path, also reporting scanner statistics
+///
+/// Shows whether an input benefits from SIMD: small inputs are mostly
+/// `scalar_tail_bytes`.
+pub fn clean_dirty_json_simd_stats(input: &str) -> Result<(String, SimdStats), ParseError> {
+    let options = CleanOptions::default();
+    let (tokens, stats) = two_stage::parse_two_stage_stats(input, &options)?;
+    Ok((reconstruct_json(&tokens, &options)?, stats))
 }
 
-/// Tokenize dirty JSON input (optimized)
-fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
-    let bytes = input.as_bytes();
+/// Pretty-print dirty JSON without altering number or string text
+///
+/// Only whitespace and indentation change; see `pretty::format_preserving`.
+/// Indents with `indent` spaces (default: 2), or one tab per level when `use_tabs` is set.
+#[wasm_bindgen]
+pub fn format_json_preserving(
+    input: &str,
+    indent: Option<usize>,
+    use_tabs: Option<bool>,
+) -> Result<String, JsValue> {
+    let style = if use_tabs.unwrap_or(false) {
+        IndentStyle::Tabs
+    } else {
+        IndentStyle::Spaces(indent.unwrap_or(2))
+    };
+    pretty::format_preserving(input, style, &CleanOptions::default())
+        .map_err(|e| JsValue::from_str(&e.message))
+}
+
+/// Pretty-print JSONC, cleaning it while keeping comments
+///
+/// Trailing commas are dropped and keys quoted; comments are re-indented to
+/// the value they precede. See `pretty::format_jsonc`.
+#[wasm_bindgen]
+pub fn format_json_with_comments(
+    input: &str,
+    indent: Option<usize>,
+    use_tabs: Option<bool>,
+) -> Result<String, JsValue> {
+    let style = if use_tabs.unwrap_or(false) {
+        IndentStyle::Tabs
+    } else {
+        IndentStyle::Spaces(indent.unwrap_or(2))
+    };
+    pretty::format_jsonc(input, style, &CleanOptions::default())
+        .map_err(|e| JsValue::from_str(&e.message))
+}
+
+/// Check whether the input contains `//` or `/* */` comments
+///
+/// Comment markers inside string literals (e.g. URLs) are ignored.
+#[wasm_bindgen]
+pub fn has_comments(input: &str) -> bool {
+    find_comment(input.as_bytes(), CommentStyles::default()).is_some()
+}
+
+/// Pre-check input for quotes, comments and nesting depth as a JSON object
+///
+/// Returns `{"balancedQuotes", "hasComments", "hasSingleQuotes", "maxDepth"}`
+/// without tokenizing; see `simd::quick_scan`.
+#[wasm_bindgen]
+pub fn quick_scan_json(input: &str) -> Result<String, JsValue> {
+    serde_json::to_string(&quick_scan(input.as_bytes()))
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Find the position of the first comment outside string literals
+pub fn find_comment(bytes: &[u8], styles: CommentStyles) -> Option<usize> {
     let len = bytes.len();
+    let mut pos = 0;
+
+    while pos < len {
+        let c = bytes[pos];
+
+        if c == b'"' || c == b'\'' {
+            pos += 1;
+            while pos < len && bytes[pos] != c {
+                if bytes[pos] == b'\\' {
+                    pos += 1;
+                }
+                pos += 1;
+            }
+        } else if comment_starts_at(bytes, pos, len, styles) {
+            return Some(pos);
+        }
+
+        pos += 1;
+    }
+
+    None
+}
+
+/// Clean dirty JSON with explicit options
+pub fn clean_with_options(input: &str, options: &CleanOptions) -> Result<String, ParseError> {
+    let tokens = tokenize(input, options)?;
+    reconstruct_json(&tokens, options)
+}
+
+/// Clean dirty JSON into a caller-provided byte buffer
+///
+/// Writes the cleaned UTF-8 bytes to `out` and returns their length, skipping
+/// the JS string conversion. When the returned length exceeds `out.length`
+/// the buffer was too small and its contents are unspecified; resize to that
+/// length and retry. wasm-bindgen copies `out` in and out of linear memory;
+/// use `clean_dirty_json_into_ptr` to write into WASM memory directly.
+#[wasm_bindgen]
+pub fn clean_dirty_json_into(input: &str, out: &mut [u8]) -> Result<usize, JsValue> {
+    clean_into_slice(input, &CleanOptions::default(), out)
+        .map_err(|e| JsValue::from_str(&e.message))
+}
+
+/// Allocate `capacity` zeroed bytes of WASM linear memory for `clean_dirty_json_into_ptr`
+///
+/// JS can view the region as `new Uint8Array(memory.buffer, ptr, capacity)`.
+/// Release it with `free_output_buffer` and the same capacity.
+#[wasm_bindgen]
+pub fn alloc_output_buffer(capacity: usize) -> *mut u8 {
+    Box::into_raw(vec![0u8; capacity].into_boxed_slice()) as *mut u8
+}
+
+/// Release a buffer from `alloc_output_buffer`
+///
+/// # Safety
+///
+/// `ptr` and `capacity` must come from one `alloc_output_buffer` call, and
+/// the buffer must not be used or freed again afterwards.
+#[wasm_bindgen]
+pub unsafe fn free_output_buffer(ptr: *mut u8, capacity: usize) {
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, capacity)));
+}
+
+/// Clean dirty JSON straight into WASM linear memory
+///
+/// Same contract as `clean_dirty_json_into`, but the cleaned bytes are
+/// streamed into `out_ptr` without passing through a JS copy.
+///
+/// # Safety
+///
+/// `out_ptr` must point to `out_cap` writable bytes, such as a buffer from
+/// `alloc_output_buffer`, that nothing else accesses during the call.
+#[wasm_bindgen]
+pub unsafe fn clean_dirty_json_into_ptr(input: &str, out_ptr: *mut u8, out_cap: usize) -> Result<usize, JsValue> {
+    let out = std::slice::from_raw_parts_mut(out_ptr, out_cap);
+    clean_into_slice(input, &CleanOptions::default(), out)
+        .map_err(|e| JsValue::from_str(&e.message))
+}
+
+/// Clean dirty JSON into `out`, returning the cleaned length
+///
+/// The output is streamed into `out` as it is reconstructed. When the
+/// returned length exceeds `out.len()` the contents of `out` are
+/// unspecified and the length is the size required.
+pub fn clean_into_slice(
+    input: &str,
+    options: &CleanOptions,
+    out: &mut [u8],
+) -> Result<usize, ParseError> {
+    if options.columnar || options.fill_width.is_some() {
+        // The document post-passes need the complete text first
+        let cleaned = clean_with_options(input, options)?;
+        if let Some(dest) = out.get_mut(..cleaned.len()) {
+            dest.copy_from_slice(cleaned.as_bytes());
+        }
+        return Ok(cleaned.len());
+    }
+
+    let tokens = tokenize(input, options)?;
+    let mut output = SliceOutput::new(out);
+    reconstruct_json_into(&tokens, options, &mut output)?;
+    Ok(output.len)
+}
+
+/// Clean dirty JSON given as UTF-8 bytes
+///
+/// Avoids decoding the input into a JS string first; see `clean_bytes_with_options`.
+#[wasm_bindgen]
+pub fn clean_dirty_json_bytes(input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    clean_bytes_with_options(input, &CleanOptions::default())
+        .map_err(|e| JsValue::from_str(&e.message))
+}
+
+/// Clean dirty JSON bytes with explicit options
+///
+/// The input is not validated as a whole: only string contents and other
+/// verbatim token text are checked, so invalid UTF-8 there fails with
+/// "Invalid UTF-8" at the first bad byte, or becomes U+FFFD with
+/// `lossy_utf8`.
+pub fn clean_bytes_with_options(input: &[u8], options: &CleanOptions) -> Result<Vec<u8>, ParseError> {
+    let capacity = options.token_capacity.unwrap_or((input.len() / 10).max(16));
+    let mut tokens = Vec::with_capacity(capacity);
+    tokenize_bytes_into(input, None, options, &mut tokens)?;
+    reconstruct_json(&tokens, options).map(String::into_bytes)
+}
+
+/// Clean NDJSON into a single JSON array
+///
+/// Each non-blank line is cleaned as its own document; see `ndjson_to_array`.
+#[wasm_bindgen]
+pub fn clean_ndjson_to_array(input: &str) -> Result<String, JsValue> {
+    ndjson_to_array(input).map_err(|e| JsValue::from_str(&e.message))
+}
+
+/// Clean each NDJSON line and wrap the results in one top-level `[...]`
+///
+/// Blank lines are skipped. A line whose brackets are not closed (such as a
+/// truncated final record) fails with its 1-based line number in the message
+/// and its byte offset as the position.
+pub fn ndjson_to_array(input: &str) -> Result<String, ParseError> {
+    let options = CleanOptions::default();
+    let mut tokens = Vec::new();
+    let mut result = String::with_capacity(input.len() + 2);
+    result.push('[');
+
+    let mut line_start = 0;
+    for (index, line) in input.split('\n').enumerate() {
+        let offset = line_start;
+        line_start += line.len() + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let at_line = |e: ParseError| {
+            ParseError::new(format!("Line {}: {}", index + 1, e.message), offset + e.position)
+        };
+        tokens.clear();
+        tokenize_into(line, &options, &mut tokens).map_err(at_line)?;
+        if !is_balanced(&tokens) {
+            return Err(at_line(ParseError::new("Incomplete record", line.len())));
+        }
+
+        if result.len() > 1 {
+            result.push(',');
+        }
+        let from = result.len();
+        reconstruct_json_into(&tokens, &options, &mut result).map_err(at_line)?;
+        finish_document(&mut result, from, &options).map_err(at_line)?;
+    }
+
+    result.push(']');
+    Ok(result)
+}
+
+/// Whether every `{` and `[` in the token stream is closed
+fn is_balanced(tokens: &[Token]) -> bool {
+    let mut depth = 0usize;
+    for token in tokens {
+        match token.token_type {
+            TokenType::LeftBrace | TokenType::LeftBracket => depth += 1,
+            TokenType::RightBrace | TokenType::RightBracket => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return false,
+            },
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+/// Reusable cleaner that keeps its token and output buffers between calls
+///
+/// Intended for callers that clean many documents in a loop: scratch
+/// buffers are cleared and reused instead of reallocated on every call.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct JsonCleanerHandle {
+    tokens: Vec<Token>,
+    output: String,
+    options: CleanOptions,
+}
+
+#[wasm_bindgen]
+impl JsonCleanerHandle {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clean dirty JSON, reusing the handle's buffers
+    pub fn clean(&mut self, input: &str) -> Result<String, JsValue> {
+        self.clean_str(input)
+            .map(str::to_owned)
+            .map_err(|e| JsValue::from_str(&e.message))
+    }
+}
 
+impl JsonCleanerHandle {
+    /// Create a handle that cleans with explicit options
+    pub fn with_options(options: CleanOptions) -> Self {
+        Self { options, ..Self::default() }
+    }
+
+    /// Clean dirty JSON into the handle's output buffer and borrow the result
+    pub fn clean_str(&mut self, input: &str) -> Result<&str, ParseError> {
+        self.tokens.clear();
+        self.output.clear();
+        tokenize_into(input, &self.options, &mut self.tokens)?;
+        reconstruct_json_into(&self.tokens, &self.options, &mut self.output)?;
+        finish_document(&mut self.output, 0, &self.options)?;
+        Ok(&self.output)
+    }
+}
+
+fn clean_dirty_json_internal(input: &str) -> Result<String, ParseError> {
+    clean_with_options(input, &CleanOptions::default())
+}
+
+/// Tokenize dirty JSON input (optimized)
+fn tokenize(input: &str, options: &CleanOptions) -> Result<Vec<Token>, ParseError> {
     // Pre-allocate tokens vector based on input size heuristic
     // Typically ~1 token per 10 characters for JSON
-    let estimated_tokens = (len / 10).max(16);
-    let mut tokens = Vec::with_capacity(estimated_tokens);
-    let mut pos = 0;
+    let capacity = options.token_capacity.unwrap_or((input.len() / 10).max(16));
+    let mut tokens = Vec::with_capacity(capacity);
+    tokenize_into(input, options, &mut tokens)?;
+    Ok(tokens)
+}
+
+/// Tokenize dirty JSON input, appending to an existing token buffer
+fn tokenize_into(input: &str, options: &CleanOptions, tokens: &mut Vec<Token>) -> Result<(), ParseError> {
+    tokenize_bytes_into(input.as_bytes(), Some(input), options, tokens)
+}
+
+/// Tokenize dirty JSON bytes, appending to an existing token buffer
+///
+/// `validated` is the input as `&str` when it is already known to be UTF-8.
+/// Otherwise only the spans that become token text (string contents and
+/// verbatim values) are validated; everything else must be ASCII anyway.
+fn tokenize_bytes_into(
+    bytes: &[u8],
+    validated: Option<&str>,
+    options: &CleanOptions,
+    tokens: &mut Vec<Token>,
+) -> Result<(), ParseError> {
+    let first_token = tokens.len();
+    scan_tokens(bytes, validated, options, |token| tokens.push(token))?;
+
+    if options.auto_array {
+        wrap_bare_sequence(tokens, first_token, bytes.len());
+    }
+
+    tokens.push(Token::new(TokenType::EOF, String::new(), bytes.len(), bytes.len()));
+    Ok(())
+}
+
+/// Tokenize dirty JSON, passing each token to `on_token` as it is scanned
+///
+/// Produces the same sequence as the cleaner's internal token buffer,
+/// including the final `EOF`, without holding the whole document's tokens.
+/// With `auto_array` the tokens are buffered first, since wrapping a bare
+/// sequence needs to see it all.
+pub fn tokenize_with(
+    input: &str,
+    options: &CleanOptions,
+    mut on_token: impl FnMut(Token),
+) -> Result<(), ParseError> {
+    if options.auto_array {
+        tokenize(input, options)?.into_iter().for_each(on_token);
+        return Ok(());
+    }
+
+    let len = input.len();
+    scan_tokens(input.as_bytes(), Some(input), options, &mut on_token)?;
+    on_token(Token::new(TokenType::EOF, String::new(), len, len));
+    Ok(())
+}
+
+/// Forwards scanned tokens, remembering the type of the last one
+struct TokenSink<F> {
+    emit: F,
+    last: Option<TokenType>,
+}
+
+impl<F: FnMut(Token)> TokenSink<F> {
+    #[inline]
+    fn push(&mut self, token: Token) {
+        self.last = Some(token.token_type);
+        (self.emit)(token);
+    }
+}
+
+/// Scan tokens up to (not including) `EOF`
+fn scan_tokens(
+    bytes: &[u8],
+    validated: Option<&str>,
+    options: &CleanOptions,
+    emit: impl FnMut(Token),
+) -> Result<(), ParseError> {
+    let text = |start: usize, end: usize| match validated {
+        Some(input) => Ok(Cow::Borrowed(&input[start..end])),
+        None if options.lossy_utf8 => Ok(String::from_utf8_lossy(&bytes[start..end])),
+        None => std::str::from_utf8(&bytes[start..end])
+            .map(Cow::Borrowed)
+            .map_err(|e| ParseError::new("Invalid UTF-8", start + e.valid_up_to())),
+    };
+    let len = bytes.len();
+    let mut tokens = TokenSink { emit, last: None };
+    let mut pos = if options.strip_xssi { xssi_prefix_len(bytes) } else { 0 };
 
     while pos < len {
         // Skip whitespace and comments
-        pos = skip_whitespace_and_comments(input, pos);
+        if options.reject_comments {
+            pos = skip_whitespace_and_comments_with(bytes, pos, len, CommentStyles::NONE);
+            if comment_starts_at(bytes, pos, len, options.comment_styles) {
+                return Err(ParseError::new("Comments are not allowed", pos));
+            }
+        } else {
+            pos = skip_whitespace_and_comments_with(bytes, pos, len, options.comment_styles);
+        }
         if pos >= len {
             break;
         }
@@ -83,14 +522,13 @@ fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
             }
 
             // Extract string slice and build value only if needed
-            let string_slice = &input[string_start..pos];
+            let string_slice = text(string_start, pos)?;
             let value = if string_slice.contains('\\') {
                 // Has escapes, need to process
                 let mut processed = String::with_capacity(string_slice.len());
-                let mut chars = string_slice.chars();
                 let mut escaped = false;
 
-                while let Some(ch) = chars.next() {
+                for ch in string_slice.chars() {
                     if escaped {
                         processed.push(ch);
                         escaped = false;
@@ -112,59 +550,33 @@ fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
             continue;
         }
 
-        // Numbers (including hex)
-        if is_digit(c) || c == '-' || c == '+' || c == '.' {
-            let mut value = String::new();
-
-            // Skip leading +
-            if c == '+' {
-                pos += 1;
-                if pos >= len {
-                    return Err(ParseError::new("Unexpected end after +", pos));
-                }
-            } else {
-                value.push(c);
-                pos += 1;
-            }
-
-            // Hex numbers
-            if pos < len && value == "0" && bytes[pos] == b'x' {
-                value.push('x');
-                pos += 1;
-
-                let hex_start = pos;
-                while pos < len {
-                    let ch = bytes[pos] as char;
-                    if ch.is_ascii_hexdigit() {
-                        value.push(ch);
-                        pos += 1;
-                    } else {
-                        break;
-                    }
-                }
-
-                if pos == hex_start {
-                    return Err(ParseError::new("Invalid hex number", start));
-                }
+        // Unquoted `${VAR}` / `{{mustache}}` placeholders become string values
+        if options.preserve_placeholders && placeholder_end(bytes, pos, len).is_some() {
+            pos = scan_placeholder_value(bytes, pos, len, options.comment_styles);
+            tokens.push(Token::new(TokenType::String, text(start, pos)?.to_string(), start, pos));
+            continue;
+        }
 
-                // Convert hex to decimal
-                if let Ok(hex_val) = u64::from_str_radix(&value[2..], 16) {
-                    value = hex_val.to_string();
-                }
-            } else {
-                // Regular numbers
-                while pos < len {
-                    let ch = bytes[pos] as char;
-                    if is_digit(ch) || ch == '.' || ch == 'e' || ch == 'E' || ch == '+' || ch == '-' {
-                        value.push(ch);
-                        pos += 1;
-                    } else {
-                        break;
-                    }
-                }
+        // Bare values after a colon are captured verbatim up to the next delimiter
+        if options.unquoted_value_until_delimiter
+            && tokens.last == Some(TokenType::Colon)
+            && !matches!(c, '{' | '[' | ',' | ']' | '}')
+        {
+            let end = scan_placeholder_value(bytes, pos, len, options.comment_styles);
+            if !is_single_scalar(bytes, pos, end, options) {
+                let value = text(pos, end)?.replace('\\', "\\\\");
+                pos = end;
+                tokens.push(Token::new(TokenType::String, value, start, pos));
+                continue;
             }
+        }
 
-            tokens.push(Token::new(TokenType::Number, value, start, pos));
+        // Numbers (including hex)
+        if is_digit(c) || c == '-' || c == '+' || c == '.' {
+            let (token_type, value, end) = number::scan_number(bytes, pos, len, options)?;
+            number::check_range(&value, start, options.overflow_numbers)?;
+            pos = end;
+            tokens.push(Token::new(token_type, value, start, pos));
             continue;
         }
 
@@ -187,13 +599,25 @@ fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
             continue;
         }
 
+        // Alternative key separators (`=>` and `=`), normalized to a colon
+        if c == '=' {
+            let arrow = pos + 1 < len && bytes[pos + 1] == b'>';
+            let separator = if arrow { KeySeparators::FAT_ARROW } else { KeySeparators::EQUALS };
+            if !options.key_separators.contains(separator) {
+                return Err(ParseError::new(format!("Unexpected character: {}", c), pos));
+            }
+            pos += if arrow { 2 } else { 1 };
+            tokens.push(Token::new(TokenType::Colon, String::new(), start, pos));
+            continue;
+        }
+
         // Single-character tokens
         let token_type = match c {
             '{' => TokenType::LeftBrace,
             '}' => TokenType::RightBrace,
             '[' => TokenType::LeftBracket,
             ']' => TokenType::RightBracket,
-            ':' => TokenType::Colon,
+            ':' if options.key_separators.contains(KeySeparators::COLON) => TokenType::Colon,
             ',' => TokenType::Comma,
             _ => return Err(ParseError::new(format!("Unexpected character: {}", c), pos)),
         };
@@ -202,114 +626,694 @@ fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
         tokens.push(Token::new(token_type, String::new(), start, pos));
     }
 
-    tokens.push(Token::new(TokenType::EOF, String::new(), len, len));
-    Ok(tokens)
+    Ok(())
 }
 
-/// Reconstruct valid JSON from tokens (optimized)
-fn reconstruct_json(tokens: &[Token]) -> String {
-    // Pre-allocate capacity based on estimated output size
-    // Estimate: input size * 1.2 (accounting for quotes and escaping)
-    let estimated_capacity = tokens.iter()
-        .map(|t| t.value.len() + 4) // value + quotes/delimiters
-        .sum::<usize>();
-    let mut result = String::with_capacity(estimated_capacity);
-    let mut i = 0;
+/// Anti-XSSI prefixes that some APIs put in front of JSON responses
+const XSSI_PREFIXES: [&[u8]; 4] = [b")]}',", b")]}'", b"while(1);", b"for(;;);"];
 
-    while i < tokens.len() {
-        let token = &tokens[i];
+/// Length of a leading anti-XSSI prefix or `#!` line, including leading whitespace
+fn xssi_prefix_len(bytes: &[u8]) -> usize {
+    let start = skip_whitespace_and_comments_with(bytes, 0, bytes.len(), CommentStyles::NONE);
+    let rest = &bytes[start..];
 
-        match token.token_type {
-            TokenType::String => {
-                // Always use double quotes
-                result.push('"');
-                // Escape any existing double quotes (optimized)
-                let bytes = token.value.as_bytes();
-                let mut last_escape = 0;
-
-                for (idx, &byte) in bytes.iter().enumerate() {
-                    if byte == b'"' {
-                        // Check if already escaped
-                        if idx == 0 || bytes[idx - 1] != b'\\' {
-                            result.push_str(&token.value[last_escape..idx]);
-                            result.push('\\');
-                            result.push('"');
-                            last_escape = idx + 1;
-                        }
-                    }
-                }
+    if rest.starts_with(b"#!") {
+        return rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(bytes.len(), |newline| start + newline + 1);
+    }
 
-                if last_escape < token.value.len() {
-                    result.push_str(&token.value[last_escape..]);
-                }
-                result.push('"');
-            }
-            TokenType::Number => {
-                result.push_str(&token.value);
-            }
-            TokenType::True => {
-                result.push_str("true");
-            }
-            TokenType::False => {
-                result.push_str("false");
-            }
-            TokenType::Null => {
-                result.push_str("null");
-            }
-            TokenType::Identifier => {
-                // Unquoted key - quote it
-                result.push('"');
-                result.push_str(&token.value);
-                result.push('"');
-            }
-            TokenType::LeftBrace => {
-                result.push('{');
-            }
-            TokenType::RightBrace => {
-                // Remove trailing comma before closing brace (optimized)
-                if let Some(&b',') = result.as_bytes().last() {
-                    result.pop();
-                }
-                result.push('}');
-            }
-            TokenType::LeftBracket => {
-                result.push('[');
-            }
-            TokenType::RightBracket => {
-                // Remove trailing comma before closing bracket (optimized)
-                if let Some(&b',') = result.as_bytes().last() {
-                    result.pop();
-                }
-                result.push(']');
-            }
-            TokenType::Colon => {
-                result.push(':');
-            }
-            TokenType::Comma => {
-                // Only add comma if next token is not a closing bracket/brace
-                if i + 1 < tokens.len() {
-                    let next = &tokens[i + 1];
-                    if !matches!(next.token_type,
-                        TokenType::RightBrace | TokenType::RightBracket | TokenType::EOF)
-                    {
-                        result.push(',');
-                    }
-                }
+    XSSI_PREFIXES
+        .iter()
+        .find(|prefix| rest.starts_with(prefix))
+        .map_or(0, |prefix| start + prefix.len())
+}
+
+/// Wrap a top-level comma-separated value sequence such as `1, 2, 3` in brackets
+///
+/// Only applies when the tokens from `first` on do not start with `{` or `[`
+/// and contain a comma outside any container.
+fn wrap_bare_sequence(tokens: &mut Vec<Token>, first: usize, len: usize) {
+    let sequence = &tokens[first..];
+    if matches!(
+        sequence.first().map(|t| t.token_type),
+        None | Some(TokenType::LeftBrace) | Some(TokenType::LeftBracket)
+    ) {
+        return;
+    }
+
+    let mut depth = 0usize;
+    let mut top_level_comma = false;
+    for token in sequence {
+        match token.token_type {
+            TokenType::LeftBrace | TokenType::LeftBracket => depth += 1,
+            TokenType::RightBrace | TokenType::RightBracket => depth = depth.saturating_sub(1),
+            TokenType::Comma if depth == 0 => {
+                top_level_comma = true;
+                break;
             }
-            TokenType::EOF => break,
+            _ => {}
         }
+    }
 
-        i += 1;
+    if top_level_comma {
+        tokens.insert(first, Token::new(TokenType::LeftBracket, String::new(), 0, 0));
+        tokens.push(Token::new(TokenType::RightBracket, String::new(), len, len));
     }
+}
 
-    result
+/// End of a `${...}` or `{{...}}` placeholder starting at `pos`, if any
+fn placeholder_end(bytes: &[u8], pos: usize, len: usize) -> Option<usize> {
+    let close: &[u8] = match bytes.get(pos..pos + 2) {
+        Some(b"${") => b"}",
+        Some(b"{{") => b"}}",
+        _ => return None,
+    };
+    let body = pos + 2;
+    bytes[body..len]
+        .windows(close.len())
+        .position(|w| w == close)
+        .map(|offset| body + offset + close.len())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Scan an unquoted value made of placeholders and surrounding text
+///
+/// The value runs until a comma, closing bracket or brace, line break, or
+/// a comment after whitespace, outside a placeholder; trailing whitespace
+/// is not included. Comment markers glued to text (`http://x`) stay part
+/// of the value. A `:` after a later word means that word is the key of
+/// the next pair (a missing comma), so the value ends before it; a colon
+/// inside the first word (`C:\tmp`, `${HOST}:80`) is kept.
+fn scan_placeholder_value(bytes: &[u8], mut pos: usize, len: usize, styles: CommentStyles) -> usize {
+    let mut end = pos;
+    // End of the text before the word being scanned, once a space was seen
+    let mut before_word = None;
+    while pos < len {
+        if end < pos && !matches!(bytes[pos], b' ' | b'\t') {
+            before_word = Some(end);
+        }
+        if let Some(placeholder) = placeholder_end(bytes, pos, len) {
+            pos = placeholder;
+            end = pos;
+            continue;
+        }
+        match bytes[pos] {
+            b',' | b']' | b'}' | b'\n' | b'\r' => break,
+            b':' if before_word.is_some() => return before_word.unwrap_or(end),
+            b' ' | b'\t' => pos += 1,
+            _ if end < pos && comment_starts_at(bytes, pos, len, styles) => break,
+            _ => {
+                pos += 1;
+                end = pos;
+            }
+        }
+    }
+    end
+}
 
-    #[test]
+/// Whether `bytes[start..end]` is exactly one number, keyword or identifier
+fn is_single_scalar(bytes: &[u8], start: usize, end: usize, options: &CleanOptions) -> bool {
+    let c = bytes[start] as char;
+    if is_identifier_start(c) {
+        return bytes[start..end].iter().all(|&b| is_identifier_char(b as char));
+    }
+    if is_digit(c) || matches!(c, '-' | '+' | '.') {
+        return number::scan_number(bytes, start, end, options)
+            .is_ok_and(|(_, _, scanned)| scanned == end);
+    }
+    false
+}
+
+/// Whether a raw key token matches any of `patterns` (see `key_matches`)
+///
+/// The key is compared as it will parse from the output, with escapes
+/// decoded, so `"\u005f_proto__"` matches `__proto__`.
+fn key_matches_any(patterns: &[String], raw_key: &str) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+    let key = if raw_key.contains('\\') {
+        // Decode what the reconstructor emits, which is always valid JSON
+        let mut quoted = String::with_capacity(raw_key.len() + 2);
+        push_quoted(&mut quoted, raw_key);
+        let decoded = unescape_json_string(&quoted[1..quoted.len() - 1]);
+        Cow::Owned(decoded.unwrap_or_else(|_| raw_key.to_string()))
+    } else {
+        Cow::Borrowed(raw_key)
+    };
+    patterns.iter().any(|pattern| key_matches(pattern, &key))
+}
+
+/// Number of tokens in a `key: value[,]` entry that `policy` drops, or 0
+fn droppable_entry_len(entry: &[Token], policy: DropPolicy) -> usize {
+    let (Some(colon), Some(value)) = (entry.get(1), entry.get(2)) else {
+        return 0;
+    };
+    if colon.token_type != TokenType::Colon {
+        return 0;
+    }
+
+    let drop = match value.token_type {
+        TokenType::Null => matches!(policy, DropPolicy::DropNullValues | DropPolicy::DropBoth),
+        TokenType::String => {
+            matches!(policy, DropPolicy::DropEmptyStrings | DropPolicy::DropBoth)
+                && value.value.trim().is_empty()
+        }
+        _ => false,
+    };
+    if !drop {
+        return 0;
+    }
+
+    match entry.get(3).map(|t| t.token_type) {
+        Some(TokenType::Comma) => 4,
+        Some(TokenType::RightBrace) => 3,
+        _ => 0,
+    }
+}
+
+/// Number of tokens in a `key: value[,]` entry, including a nested container value
+fn entry_len(entry: &[Token]) -> usize {
+    let mut len = 1;
+    if entry.get(len).map(|t| t.token_type) == Some(TokenType::Colon) {
+        len += 1;
+    }
+
+    let mut depth = 0usize;
+    while let Some(token) = entry.get(len) {
+        match token.token_type {
+            TokenType::LeftBrace | TokenType::LeftBracket => depth += 1,
+            TokenType::RightBrace | TokenType::RightBracket if depth == 0 => return len,
+            TokenType::RightBrace | TokenType::RightBracket => depth -= 1,
+            TokenType::Comma if depth == 0 => return len + 1,
+            TokenType::EOF => return len,
+            _ => {}
+        }
+        len += 1;
+    }
+    len
+}
+
+/// Offset of the closing brace when `object` (starting at its `{`) has a
+/// single entry whose key matches one of `patterns`
+fn wrapper_close(object: &[Token], patterns: &[String]) -> Option<usize> {
+    let key = object.get(1)?;
+    if !matches!(key.token_type, TokenType::String | TokenType::Identifier)
+        || object.get(2)?.token_type != TokenType::Colon
+        || !key_matches_any(patterns, &key.value)
+    {
+        return None;
+    }
+
+    let len = entry_len(&object[1..]);
+    let has_value = object[3..1 + len]
+        .iter()
+        .any(|t| t.token_type != TokenType::Comma);
+    (has_value && object.get(1 + len)?.token_type == TokenType::RightBrace).then_some(1 + len)
+}
+
+/// Whether a token can begin a value or key
+fn starts_value(token_type: TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::String
+            | TokenType::Number
+            | TokenType::True
+            | TokenType::False
+            | TokenType::Null
+            | TokenType::Identifier
+            | TokenType::LeftBrace
+            | TokenType::LeftBracket
+    )
+}
+
+/// Whether a token can complete a value
+fn ends_value(token_type: TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::String
+            | TokenType::Number
+            | TokenType::True
+            | TokenType::False
+            | TokenType::Null
+            | TokenType::Identifier
+            | TokenType::RightBrace
+            | TokenType::RightBracket
+    )
+}
+
+/// Reconstruct valid JSON from tokens (optimized)
+fn reconstruct_json(tokens: &[Token], options: &CleanOptions) -> Result<String, ParseError> {
+    // The columnar and fill_width passes rewrite the document, so a measured
+    // size would be wrong for the final text; those fall back to the estimate
+    let rewritten = options.columnar || options.fill_width.is_some();
+    let capacity = if options.exact_output_size && !rewritten {
+        // Measure with a dry run so the result is allocated exactly once
+        let mut counter = SizeCounter::default();
+        reconstruct_json_into(tokens, options, &mut counter)?;
+        counter.len
+    } else {
+        // Pre-allocate capacity based on estimated output size
+        // Estimate: input size * 1.2 (accounting for quotes and escaping)
+        options.output_capacity.unwrap_or_else(|| {
+            tokens.iter()
+                .map(|t| t.value.len() + 4) // value + quotes/delimiters
+                .sum::<usize>()
+        })
+    };
+    let mut result = String::with_capacity(capacity);
+    reconstruct_json_into(tokens, options, &mut result)?;
+    finish_document(&mut result, 0, options)?;
+    Ok(result)
+}
+
+/// Apply the whole-document passes to the document in `result[from..]`
+///
+/// These need the complete reconstructed text, so every entry point that
+/// writes a finished document calls this after `reconstruct_json_into`.
+/// A `columnar` failure is positioned in the reconstructed document, since
+/// the text it rejects is cleaned output rather than input.
+fn finish_document(result: &mut String, from: usize, options: &CleanOptions) -> Result<(), ParseError> {
+    if options.columnar {
+        let document = &result[from..];
+        let value: serde_json::Value = serde_json::from_str(document).map_err(|e| {
+            let message = format!("Columnar output needs strict JSON: {}", e);
+            ParseError::new(message, json_error_offset(document, &e))
+        })?;
+        result.truncate(from);
+        result.push_str(&columnar::to_columnar(value).to_string());
+    }
+    if let Some(max_width) = options.fill_width {
+        let filled = pretty::fill_layout(&result[from..], max_width);
+        result.truncate(from);
+        result.push_str(&filled);
+    }
+    Ok(())
+}
+
+/// Byte offset in `text` of the line and column a serde_json error reports
+fn json_error_offset(text: &str, error: &serde_json::Error) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(error.line().saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + error.column().saturating_sub(1)).min(text.len())
+}
+
+/// Reconstruct valid JSON from tokens, appending to an existing buffer
+fn reconstruct_json_into<O: Output>(
+    tokens: &[Token],
+    options: &CleanOptions,
+    result: &mut O,
+) -> Result<(), ParseError> {
+    let mut keys = KeyTracker::default();
+    let mut prev: Option<TokenType> = None;
+    // Closing braces (and trailing commas) of unwrapped objects, innermost last
+    let mut unwrapped: Vec<usize> = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = &tokens[i];
+
+        if unwrapped.last() == Some(&i) {
+            unwrapped.pop();
+            i += 1;
+            continue;
+        }
+
+        // Two adjacent complete values inside a container lost their comma
+        if options.insert_missing_commas
+            && (keys.in_object() || keys.in_array())
+            && prev.is_some_and(ends_value)
+            && starts_value(token.token_type)
+        {
+            result.push(',');
+            keys.observe(TokenType::Comma);
+            prev = Some(TokenType::Comma);
+        }
+
+        let is_key = keys.is_key();
+
+        // Skip `key: value` entries whose value the drop policy removes,
+        // together with their following comma
+        if is_key && options.drop_empty != DropPolicy::Keep {
+            let skip = droppable_entry_len(&tokens[i..], options.drop_empty);
+            if skip > 0 {
+                for skipped in &tokens[i..i + skip] {
+                    keys.observe(skipped.token_type);
+                    prev = Some(skipped.token_type);
+                }
+                i += skip;
+                continue;
+            }
+        }
+
+        // Skip whole entries whose key matches `drop_keys`, nested values included
+        if is_key
+            && matches!(
+                token.token_type,
+                TokenType::String | TokenType::Identifier | TokenType::Number
+            )
+            && key_matches_any(&options.drop_keys, &token.value)
+        {
+            let skip = entry_len(&tokens[i..]);
+            for skipped in &tokens[i..i + skip] {
+                keys.observe(skipped.token_type);
+                prev = Some(skipped.token_type);
+            }
+            i += skip;
+            continue;
+        }
+
+        // Replace a single-entry wrapper object with its value
+        if token.token_type == TokenType::LeftBrace && !options.unwrap_keys.is_empty() {
+            if let Some(close) = wrapper_close(&tokens[i..], &options.unwrap_keys) {
+                unwrapped.push(i + close);
+                if tokens[i + close - 1].token_type == TokenType::Comma {
+                    unwrapped.push(i + close - 1);
+                }
+                i += 3;
+                continue;
+            }
+        }
+
+        match token.token_type {
+            TokenType::String => {
+                if !is_key && options.parse_nested_json_strings {
+                    if let Some(json) = nested::rewrite_nested_json(&token.value, options) {
+                        result.push_str(&json);
+                        keys.observe(token.token_type);
+                        prev = Some(token.token_type);
+                        i += 1;
+                        continue;
+                    }
+                }
+
+                if !is_key
+                    && options.coerce_string_scalars
+                    && classify_scalar(&token.value) != ScalarKind::String
+                {
+                    result.push_str(&token.value);
+                    keys.observe(token.token_type);
+                    prev = Some(token.token_type);
+                    i += 1;
+                    continue;
+                }
+
+                let value = if is_key {
+                    apply_key_case(&token.value, options.key_case)
+                } else if options.normalize_newlines {
+                    normalize_newlines(&token.value)
+                } else {
+                    Cow::Borrowed(token.value.as_str())
+                };
+                let value = if options.expand_env && !is_key {
+                    match env::expand_env(&value, options) {
+                        Ok(expanded) => Cow::Owned(expanded.into_owned()),
+                        Err(name) => {
+                            return Err(ParseError::new(
+                                format!("Unset environment variable: {}", name),
+                                token.start,
+                            ));
+                        }
+                    }
+                } else {
+                    value
+                };
+                #[cfg(feature = "unicode-normalize")]
+                let value = match options.unicode_normalize {
+                    Some(form) if !is_key || options.unicode_normalize_keys => {
+                        Cow::Owned(unicode::normalize_raw(&value, form).into_owned())
+                    }
+                    _ => value,
+                };
+
+                push_quoted_with(result, &value, options.minimize_escapes, options.ascii_safe);
+            }
+            TokenType::Number => {
+                if is_key {
+                    // Numeric keys are not valid JSON - quote them
+                    push_quoted(result, &token.value);
+                } else if options.overflow_numbers == OverflowPolicy::Null
+                    && number::is_out_of_range(&token.value)
+                {
+                    result.push_str("null");
+                } else if options.normalize_number_format
+                    && options.leading_zero_strings
+                    && number::has_leading_zero(&token.value)
+                {
+                    push_quoted(result, &token.value);
+                } else {
+                    let mut value = Cow::Borrowed(token.value.as_str());
+                    if let Some(rounded) =
+                        options.round_floats.and_then(|digits| number::round_significant(&value, digits))
+                    {
+                        value = Cow::Owned(rounded);
+                    }
+                    if options.normalize_number_format {
+                        if let Some(canonical) = number::canonical_number(&value) {
+                            value = Cow::Owned(canonical);
+                        }
+                    }
+                    result.push_str(&value);
+                }
+            }
+            TokenType::True => {
+                result.push_str("true");
+            }
+            TokenType::False => {
+                result.push_str("false");
+            }
+            TokenType::Null => {
+                result.push_str("null");
+            }
+            TokenType::Identifier => {
+                if is_key {
+                    // Unquoted key - quote it
+                    result.push('"');
+                    result.push_str(&apply_key_case(&token.value, options.key_case));
+                    result.push('"');
+                } else {
+                    match options.identifier_values {
+                        IdentifierPolicy::QuoteAsString => {
+                            result.push('"');
+                            result.push_str(&token.value);
+                            result.push('"');
+                        }
+                        IdentifierPolicy::Null => result.push_str("null"),
+                        IdentifierPolicy::Error => {
+                            return Err(ParseError::new(
+                                format!("Unexpected identifier: {}", token.value),
+                                token.start,
+                            ));
+                        }
+                    }
+                }
+            }
+            TokenType::LeftBrace => {
+                result.push('{');
+            }
+            TokenType::RightBrace => {
+                // Remove trailing comma before closing brace
+                result.pop_comma();
+                result.push('}');
+            }
+            TokenType::LeftBracket => {
+                result.push('[');
+            }
+            TokenType::RightBracket => {
+                // Remove trailing comma before closing bracket
+                result.pop_comma();
+                result.push(']');
+            }
+            TokenType::Colon => {
+                result.push(':');
+            }
+            TokenType::Comma => {
+                // Consecutive commas (or a leading comma) mark an elided element
+                let hole = matches!(
+                    prev,
+                    Some(TokenType::Comma | TokenType::LeftBracket | TokenType::LeftBrace)
+                );
+                if hole && (!keys.in_array() || options.array_holes == HolePolicy::Drop) {
+                    keys.observe(token.token_type);
+                    prev = Some(token.token_type);
+                    i += 1;
+                    continue;
+                }
+                if hole {
+                    result.push_str("null");
+                }
+
+                // Only add comma if next token is not a closing bracket/brace
+                if i + 1 < tokens.len() {
+                    let next = &tokens[i + 1];
+                    if !matches!(next.token_type,
+                        TokenType::RightBrace | TokenType::RightBracket | TokenType::EOF)
+                    {
+                        result.push(',');
+                    }
+                }
+            }
+            TokenType::EOF => break,
+        }
+
+        keys.observe(token.token_type);
+        prev = Some(token.token_type);
+        i += 1;
+
+        // A key directly followed by its value lost its colon
+        if options.insert_missing_colons
+            && is_key
+            && matches!(
+                token.token_type,
+                TokenType::String | TokenType::Identifier | TokenType::Number
+            )
+            && tokens.get(i).is_some_and(|next| starts_value(next.token_type))
+        {
+            result.push(':');
+            keys.observe(TokenType::Colon);
+            prev = Some(TokenType::Colon);
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrite raw or escaped `\r\n` and `\r` line breaks in a string value as `\n` escapes
+///
+/// Raw line feeds are escaped as well, since they are not valid inside JSON strings.
+fn normalize_newlines(value: &str) -> Cow<'_, str> {
+    if !value.contains(['\r', '\n']) && !value.contains("\\r") {
+        return Cow::Borrowed(value);
+    }
+
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                out.push_str("\\n");
+            }
+            '\n' => out.push_str("\\n"),
+            '\\' => match chars.next() {
+                Some('r') => {
+                    let mut rest = chars.clone();
+                    if rest.next() == Some('\\') && rest.next() == Some('n') {
+                        chars = rest;
+                    }
+                    out.push_str("\\n");
+                }
+                Some(escaped) => {
+                    out.push('\\');
+                    out.push(escaped);
+                }
+                None => out.push('\\'),
+            },
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Append a raw string token value as a double-quoted JSON string
+///
+/// Valid JSON escapes in the source are kept as written; other escapes such
+/// as `\'` decode to the escaped character. Unescaped quotes and control
+/// characters are escaped.
+fn push_quoted<O: Output>(result: &mut O, value: &str) {
+    push_quoted_with(result, value, false, false);
+}
+
+/// Append a raw string token value as a double-quoted JSON string
+///
+/// With `minimize`, escapes JSON doesn't require are decoded: `\/` becomes
+/// `/` and `\uXXXX` becomes the literal character when it is printable and
+/// not a surrogate. Quotes, backslashes and control characters stay escaped.
+/// With `ascii`, every non-ASCII character is written as a `\uXXXX` escape
+/// (a surrogate pair above U+FFFF), and `minimize` only decodes escapes of
+/// ASCII characters.
+fn push_quoted_with<O: Output>(result: &mut O, value: &str, minimize: bool, ascii: bool) {
+    // Always use double quotes
+    result.push('"');
+
+    let clean = !value.bytes().any(|b| b == b'"' || b == b'\\' || b < 0x20 || (ascii && b >= 0x80));
+    if clean {
+        result.push_str(value);
+        result.push('"');
+        return;
+    }
+
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ascii && !ch.is_ascii() {
+            push_unicode_escape(result, ch);
+            continue;
+        }
+        if ch != '\\' {
+            result.push_escaped(ch);
+            continue;
+        }
+
+        let rest = chars.as_str();
+        match rest.chars().next() {
+            Some('/') if minimize => {
+                result.push('/');
+                chars.next();
+            }
+            Some(c @ ('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't')) => {
+                result.push('\\');
+                result.push(c);
+                chars.next();
+            }
+            Some('u') if rest.len() >= 5 && rest.as_bytes()[1..5].iter().all(u8::is_ascii_hexdigit) => {
+                match u32::from_str_radix(&rest[1..5], 16).ok().and_then(char::from_u32) {
+                    Some(c)
+                        if minimize
+                            && !c.is_control()
+                            && !matches!(c, '"' | '\\')
+                            && (c.is_ascii() || !ascii) =>
+                    {
+                        result.push(c);
+                    }
+                    _ => {
+                        result.push('\\');
+                        result.push_str(&rest[..5]);
+                    }
+                }
+                chars.nth(4);
+            }
+            Some(c) if ascii && !c.is_ascii() => {
+                push_unicode_escape(result, c);
+                chars.next();
+            }
+            Some(c) => {
+                result.push_escaped(c);
+                chars.next();
+            }
+            None => result.push_str("\\\\"),
+        }
+    }
+
+    result.push('"');
+}
+
+/// Append `ch` as `\uXXXX` escapes of its UTF-16 code units
+fn push_unicode_escape<O: Output>(result: &mut O, ch: char) {
+    let mut units = [0u16; 2];
+    for unit in ch.encode_utf16(&mut units) {
+        result.push_str(&format!("\\u{:04x}", unit));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
     fn test_clean_simple_dirty_json() {
         let input = "{name: 'alice', age: 30}";
         let result = clean_dirty_json_internal(input).unwrap();
@@ -330,6 +1334,32 @@ mod tests {
         assert!(result.contains(r#""bob""#));
     }
 
+    #[test]
+    fn test_clean_hash_comments() {
+        let options = CleanOptions {
+            comment_styles: CommentStyles::default() | CommentStyles::HASH,
+            ..Default::default()
+        };
+        let input = "{ a: 1 # comment\n, b: '#not a comment' }";
+        let result = clean_with_options(input, &options).unwrap();
+        assert_eq!(result, r##"{"a":1,"b":"#not a comment"}"##);
+
+        assert!(clean_dirty_json_internal("{ a: 1 # comment\n }").is_err());
+    }
+
+    #[test]
+    fn test_has_comments() {
+        assert!(!has_comments(r#"{"u":"http://x"}"#));
+        assert!(!has_comments(r#"{'u':'a/*b*/'}"#));
+        assert!(has_comments(r#"{} // x"#));
+        assert!(has_comments("{a: /* b */ 1}"));
+
+        let strict = CleanOptions { reject_comments: true, ..Default::default() };
+        let err = clean_with_options("{a: 1 // note\n}", &strict).unwrap_err();
+        assert_eq!(err.position, 6);
+        assert!(clean_with_options(r#"{"u":"http://x"}"#, &strict).is_ok());
+    }
+
     #[test]
     fn test_clean_trailing_commas() {
         let input = r#"{"items": [1, 2, 3,], "total": 3,}"#;
@@ -337,10 +1367,568 @@ mod tests {
         assert_eq!(result, r#"{"items":[1,2,3],"total":3}"#);
     }
 
+    #[test]
+    fn test_array_holes() {
+        let input = "[[1,,2], [,1], [1,,], {a: 1,, b: 2}]";
+
+        let result = clean_dirty_json_internal(input).unwrap();
+        assert_eq!(result, r#"[[1,null,2],[null,1],[1,null],{"a":1,"b":2}]"#);
+
+        let drop = CleanOptions { array_holes: HolePolicy::Drop, ..Default::default() };
+        let result = clean_with_options(input, &drop).unwrap();
+        assert_eq!(result, r#"[[1,2],[1],[1],{"a":1,"b":2}]"#);
+
+        // A single trailing comma is not a hole
+        assert_eq!(clean_dirty_json_internal("[1,2,]").unwrap(), "[1,2]");
+    }
+
+    #[test]
+    fn test_cleaner_handle_matches_stateless() {
+        let mut handle = JsonCleanerHandle::new();
+        let inputs = [
+            "{name: 'alice', age: 30,}",
+            "[1,,2, // comment\n 3]",
+            "{a: {b: [true, false, null]}}",
+            "{broken: ",
+        ];
+
+        for _ in 0..3 {
+            for input in inputs {
+                let expected = clean_dirty_json_internal(input).map_err(|e| e.message);
+                let actual = handle.clean_str(input).map(str::to_owned).map_err(|e| e.message);
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_separators() {
+        let options = CleanOptions {
+            key_separators: KeySeparators::ALL,
+            ..CleanOptions::default()
+        };
+        assert_eq!(clean_with_options("{a => 1, b => 2}", &options).unwrap(), r#"{"a":1,"b":2}"#);
+        assert_eq!(clean_with_options("{a = 1}", &options).unwrap(), r#"{"a":1}"#);
+        assert_eq!(clean_with_options("{a: 'x=>y'}", &options).unwrap(), r#"{"a":"x=>y"}"#);
+
+        // Colon only by default
+        assert!(clean_dirty_json_internal("{a => 1}").is_err());
+        assert!(clean_dirty_json_internal("{a = 1}").is_err());
+    }
+
+    #[test]
+    fn test_preserve_placeholders() {
+        let options = CleanOptions {
+            preserve_placeholders: true,
+            ..CleanOptions::default()
+        };
+        assert_eq!(
+            clean_with_options("{url: ${BASE}/api, name: {{ user.name }} }", &options).unwrap(),
+            r#"{"url":"${BASE}/api","name":"{{ user.name }}"}"#
+        );
+        assert_eq!(
+            clean_with_options("[${A}, {{b}}, 'x']", &options).unwrap(),
+            r#"["${A}","{{b}}","x"]"#
+        );
+        let options = CleanOptions { insert_missing_commas: true, ..options };
+        assert_eq!(
+            clean_with_options("{host: ${HOST}:${PORT}, a: ${X} b: 1}", &options).unwrap(),
+            r#"{"host":"${HOST}:${PORT}","a":"${X}","b":1}"#
+        );
+    }
+
+    #[test]
+    fn test_bom_and_normalize_newlines() {
+        let input = "\u{FEFF}{text: 'line1\r\nline2\rline3', escaped: \"a\\r\\nb\", path: 'C:\\\\rs'}";
+        let options = CleanOptions {
+            normalize_newlines: true,
+            ..CleanOptions::default()
+        };
+        assert_eq!(
+            clean_with_options(input, &options).unwrap(),
+            r#"{"text":"line1\nline2\nline3","escaped":"a\nb","path":"C:\\rs"}"#
+        );
+
+        // BOM is always skipped; newlines only change when requested
+        assert_eq!(clean_dirty_json_internal("\u{FEFF}[1]").unwrap(), "[1]");
+    }
+
+    #[test]
+    fn test_overflow_numbers() {
+        let input = r#"{"x": 1e400, "y": -1e400, "z": 1e300}"#;
+        assert_eq!(clean_dirty_json_internal(input).unwrap(), r#"{"x":1e400,"y":-1e400,"z":1e300}"#);
+
+        let null = CleanOptions {
+            overflow_numbers: OverflowPolicy::Null,
+            ..CleanOptions::default()
+        };
+        assert_eq!(clean_with_options(input, &null).unwrap(), r#"{"x":null,"y":null,"z":1e300}"#);
+
+        let error = CleanOptions {
+            overflow_numbers: OverflowPolicy::Error,
+            ..CleanOptions::default()
+        };
+        assert_eq!(clean_with_options(input, &error).unwrap_err().position, 6);
+    }
+
+    #[test]
+    fn test_auto_array() {
+        let options = CleanOptions {
+            auto_array: true,
+            ..CleanOptions::default()
+        };
+        assert_eq!(clean_with_options("1, 2, 3", &options).unwrap(), "[1,2,3]");
+        assert_eq!(clean_with_options("'a', {b: [1, 2]},", &options).unwrap(), r#"["a",{"b":[1,2]}]"#);
+        assert_eq!(clean_with_options("[1, 2]", &options).unwrap(), "[1,2]");
+        assert_eq!(clean_with_options("42", &options).unwrap(), "42");
+    }
+
+    #[test]
+    fn test_string_escaping() {
+        let input = "{a: 'it\\'s', b: \"tab\there\", c: 'q\"x', d: \"\\u0041\\\\\", e: 'ctrl\u{1}'}";
+        let result = clean_dirty_json_internal(input).unwrap();
+        assert_eq!(result, r#"{"a":"it's","b":"tab\there","c":"q\"x","d":"\u0041\\","e":"ctrl\u0001"}"#);
+        assert!(serde_json::from_str::<serde_json::Value>(&result).is_ok());
+    }
+
+    #[test]
+    fn test_ndjson_to_array() {
+        let input = "{a: 1}\n\n{'b': [2,]}\r\n[3]\n";
+        assert_eq!(ndjson_to_array(input).unwrap(), r#"[{"a":1},{"b":[2]},[3]]"#);
+        assert_eq!(ndjson_to_array("").unwrap(), "[]");
+
+        let err = ndjson_to_array("{a: 1}\n{b: [2").unwrap_err();
+        assert!(err.message.starts_with("Line 2:"));
+        assert_eq!(err.position, 13);
+    }
+
+    #[test]
+    fn test_strip_xssi() {
+        let options = CleanOptions {
+            strip_xssi: true,
+            ..CleanOptions::default()
+        };
+        for input in [
+            ")]}'\n{\"a\":1}",
+            ")]}',\n{\"a\":1}",
+            "while(1);{\"a\":1}",
+            "for(;;); {\"a\":1}",
+            "#!/usr/bin/env molt\n{a: 1}",
+        ] {
+            assert_eq!(clean_with_options(input, &options).unwrap(), r#"{"a":1}"#);
+        }
+        assert!(clean_dirty_json_internal(")]}'\n{\"a\":1}").is_err());
+    }
+
+    #[test]
+    fn test_big_hex_is_valid_json() {
+        let result = clean_dirty_json_internal("{big: 0x123456789ABCDEF01234, neg: -0xFFFFFFFFFFFFFFFFFF}").unwrap();
+        assert_eq!(result, r#"{"big":"85968058283706962416180","neg":"-4722366482869645213695"}"#);
+        assert!(serde_json::from_str::<serde_json::Value>(&result).is_ok());
+    }
+
+    #[test]
+    fn test_drop_empty() {
+        let input = r#"{"a": "", "b": null, "c": 1, "d": "  ", "e": [null, ""]}"#;
+        let clean = |drop_empty| {
+            let options = CleanOptions { drop_empty, ..CleanOptions::default() };
+            clean_with_options(input, &options).unwrap()
+        };
+
+        assert_eq!(clean(DropPolicy::Keep), r#"{"a":"","b":null,"c":1,"d":"  ","e":[null,""]}"#);
+        assert_eq!(clean(DropPolicy::DropNullValues), r#"{"a":"","c":1,"d":"  ","e":[null,""]}"#);
+        assert_eq!(clean(DropPolicy::DropEmptyStrings), r#"{"b":null,"c":1,"e":[null,""]}"#);
+        assert_eq!(clean(DropPolicy::DropBoth), r#"{"c":1,"e":[null,""]}"#);
+
+        let options = CleanOptions { drop_empty: DropPolicy::DropBoth, ..CleanOptions::default() };
+        assert_eq!(clean_with_options("{a: 1, b: null}", &options).unwrap(), r#"{"a":1}"#);
+        assert_eq!(clean_with_options("{b: null}", &options).unwrap(), "{}");
+    }
+
+    #[test]
+    fn test_clean_into_slice() {
+        let input = "{a: 'x', b: [1, 2,],}";
+        let mut small = [0u8; 4];
+        let needed = clean_into_slice(input, &CleanOptions::default(), &mut small).unwrap();
+        assert_eq!(needed, 19);
+
+        let mut out = vec![0u8; needed];
+        let written = clean_into_slice(input, &CleanOptions::default(), &mut out).unwrap();
+        assert_eq!(&out[..written], br#"{"a":"x","b":[1,2]}"#);
+
+        let mut exact = vec![0u8; 8];
+        assert_eq!(clean_into_slice("[1, 2,]", &CleanOptions::default(), &mut exact).unwrap(), 5);
+        assert_eq!(&exact[..5], b"[1,2]");
+
+        let options = CleanOptions { fill_width: Some(24), ..CleanOptions::default() };
+        let mut filled = vec![0u8; 16];
+        let written = clean_into_slice("[1,2]", &options, &mut filled).unwrap();
+        assert_eq!(&filled[..written], b"[1, 2]");
+    }
+
+    #[test]
+    fn test_clean_dirty_json_into_ptr() {
+        let input = "{a: 'x', b: [1, 2,],}";
+        let ptr = alloc_output_buffer(32);
+        let written = unsafe { clean_dirty_json_into_ptr(input, ptr, 32) }.unwrap();
+        let out = unsafe { std::slice::from_raw_parts(ptr, written) };
+        assert_eq!(out, br#"{"a":"x","b":[1,2]}"#);
+        unsafe { free_output_buffer(ptr, 32) };
+    }
+
+    #[test]
+    fn test_minimize_escapes() {
+        let options = CleanOptions { minimize_escapes: true, ..CleanOptions::default() };
+        let clean = |input| clean_with_options(input, &options).unwrap();
+
+        assert_eq!(clean(r#"["\/x\u0041"]"#), r#"["/xA"]"#);
+        assert_eq!(
+            clean(r#"{"k\/": "\u00e9\n\"\\\u0022\u005c\u0001\ud83d\ude00"}"#),
+            r#"{"k/":"é\n\"\\\u0022\u005c\u0001\ud83d\ude00"}"#
+        );
+        assert_eq!(clean_dirty_json_internal(r#"["\/x\u0041"]"#).unwrap(), r#"["\/x\u0041"]"#);
+    }
+
+    #[test]
+    fn test_ascii_safe() {
+        let options = CleanOptions { ascii_safe: true, ..CleanOptions::default() };
+        let input = "{'name':'caf\u{e9}\u{1f642}', 'note': \"\\u00e9 \\u0041\\'\u{e9}\"}";
+        let cleaned = clean_with_options(input, &options).unwrap();
+        assert_eq!(
+            cleaned,
+            r#"{"name":"caf\u00e9\ud83d\ude42","note":"\u00e9 \u0041'\u00e9"}"#
+        );
+        assert!(cleaned.is_ascii());
+
+        let value: serde_json::Value = serde_json::from_str(&cleaned).unwrap();
+        assert_eq!(value["name"], "caf\u{e9}\u{1f642}");
+
+        // Minimizing still decodes escapes of ASCII characters only
+        let options = CleanOptions { minimize_escapes: true, ..options };
+        assert_eq!(
+            clean_with_options(r#"["\u0041\u00e9"]"#, &options).unwrap(),
+            r#"["A\u00e9"]"#
+        );
+    }
+
+    #[test]
+    fn test_insert_missing_commas() {
+        let options = CleanOptions { insert_missing_commas: true, ..CleanOptions::default() };
+        let clean = |input| clean_with_options(input, &options).unwrap();
+
+        assert_eq!(clean("[1 2 3]"), "[1,2,3]");
+        assert_eq!(clean(r#"{"a":1 "b":2}"#), r#"{"a":1,"b":2}"#);
+        assert_eq!(
+            clean("{\n  a: [1, 2] \n  b: {c: 'x'}\n  d: true\n}"),
+            r#"{"a":[1,2],"b":{"c":"x"},"d":true}"#
+        );
+    }
+
+    #[cfg(feature = "unicode-normalize")]
+    #[test]
+    fn test_unicode_normalize() {
+        let options = CleanOptions {
+            unicode_normalize: Some(NormForm::Nfc),
+            ..CleanOptions::default()
+        };
+        let input = "{'cafe\u{301}': 'cafe\u{301}', n: 1}";
+        assert_eq!(
+            clean_with_options(input, &options).unwrap(),
+            "{\"cafe\u{301}\":\"caf\u{e9}\",\"n\":1}"
+        );
+
+        let keys = CleanOptions { unicode_normalize_keys: true, ..options };
+        assert_eq!(
+            clean_with_options(input, &keys).unwrap(),
+            "{\"caf\u{e9}\":\"caf\u{e9}\",\"n\":1}"
+        );
+    }
+
+    #[test]
+    fn test_exact_output_size() {
+        let input = "{a: 'x\\'y', b: [1, 2,,], c: {d: null,}, 'e\\u00e9': \"\\/\",} // end";
+        let exact = CleanOptions { exact_output_size: true, ..CleanOptions::default() };
+        let tokens = tokenize(input, &exact).unwrap();
+        let result = reconstruct_json(&tokens, &exact).unwrap();
+        assert_eq!(result, clean_with_options(input, &CleanOptions::default()).unwrap());
+        assert_eq!(result.capacity(), result.len());
+
+        let hinted = CleanOptions { token_capacity: Some(8), output_capacity: Some(7), ..CleanOptions::default() };
+        let tokens = tokenize("[1]", &hinted).unwrap();
+        assert_eq!(tokens.capacity(), 8);
+        assert_eq!(reconstruct_json(&tokens, &hinted).unwrap().capacity(), 7);
+
+        // Passes that rewrite the document skip the dry run
+        let input = "[{a: 1, b: 2}, {a: 3, b: 4}]";
+        for options in [
+            CleanOptions { columnar: true, ..CleanOptions::default() },
+            CleanOptions { fill_width: Some(10), ..CleanOptions::default() },
+        ] {
+            let exact = CleanOptions { exact_output_size: true, ..options.clone() };
+            assert_eq!(clean_with_options(input, &exact).unwrap(), clean_with_options(input, &options).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_unquoted_value_until_delimiter() {
+        let options = CleanOptions { unquoted_value_until_delimiter: true, ..CleanOptions::default() };
+        assert_eq!(
+            clean_with_options("{path: /usr/bin, unit: m/s}", &options).unwrap(),
+            r#"{"path":"/usr/bin","unit":"m/s"}"#
+        );
+        assert_eq!(
+            clean_with_options("{n: 1.5, ok: true, id: abc, dir: C:\\tmp x, list: [a]}", &options).unwrap(),
+            r#"{"n":1.5,"ok":true,"id":"abc","dir":"C:\\tmp x","list":["a"]}"#
+        );
+        assert_eq!(clean_with_options("{port: 8080 // http\n}", &options).unwrap(), r#"{"port":8080}"#);
+        assert_eq!(
+            clean_with_options("{port: 8080 /* http */, host: my box /* lan */}", &options).unwrap(),
+            r#"{"port":8080,"host":"my box"}"#
+        );
+        assert_eq!(
+            clean_with_options("{url: http://x/y // home\n}", &options).unwrap(),
+            r#"{"url":"http://x/y"}"#
+        );
+    }
+
+    #[test]
+    fn test_clean_bytes() {
+        let input = "{name: 'caf\u{e9} \u{1f600}', tags: ['\u{4e2d}',], // \u{fc}\n}";
+        let cleaned = clean_bytes_with_options(input.as_bytes(), &CleanOptions::default()).unwrap();
+        assert_eq!(cleaned, clean_dirty_json_internal(input).unwrap().into_bytes());
+
+        let err = clean_bytes_with_options(b"{a: 'x\xff'}", &CleanOptions::default()).unwrap_err();
+        assert_eq!((err.message.as_str(), err.position), ("Invalid UTF-8", 6));
+
+        let lossy = CleanOptions { lossy_utf8: true, ..CleanOptions::default() };
+        let cleaned = clean_bytes_with_options(b"{a: 'x\xff', b: [\"\xfe\xfe\"]}", &lossy).unwrap();
+        assert_eq!(String::from_utf8(cleaned).unwrap(), "{\"a\":\"x\u{fffd}\",\"b\":[\"\u{fffd}\u{fffd}\"]}");
+    }
+
+    #[test]
+    fn test_insert_missing_colons() {
+        let options = CleanOptions { insert_missing_colons: true, ..CleanOptions::default() };
+        assert_eq!(
+            clean_with_options("{name \"alice\", age 30}", &options).unwrap(),
+            r#"{"name":"alice","age":30}"#
+        );
+        assert_eq!(
+            clean_with_options("{a {b [1]}, c: 2}", &options).unwrap(),
+            r#"{"a":{"b":[1]},"c":2}"#
+        );
+    }
+
+    #[test]
+    fn test_normalize_number_format() {
+        let options = CleanOptions { normalize_number_format: true, ..CleanOptions::default() };
+        let input = "[1.2000, 007, 1., 0, 0.0, 2.50e-3, -0]";
+        assert_eq!(clean_with_options(input, &options).unwrap(), "[1.2,7,1,0,0,2.5e-3,-0]");
+
+        let options = CleanOptions { leading_zero_strings: true, ..options };
+        assert_eq!(clean_with_options("{zip: 007, n: 1.0}", &options).unwrap(), r#"{"zip":"007","n":1}"#);
+    }
+
+    #[test]
+    fn test_round_floats() {
+        let options = CleanOptions { round_floats: Some(3), ..CleanOptions::default() };
+        assert_eq!(
+            clean_with_options("{t: 21.456789, v: [0.333333, 1.5, 1234567], e: 6.02214e23}", &options).unwrap(),
+            r#"{"t":21.5,"v":[0.333,1.5,1234567],"e":6.02e23}"#
+        );
+    }
+
+    #[test]
+    fn test_tokenize_with() {
+        let input = "{a: 'x', b: [1, 2.5e3, true,], /* c */ d: null}";
+        for options in [CleanOptions::default(), CleanOptions { auto_array: true, ..CleanOptions::default() }] {
+            let mut streamed = Vec::new();
+            tokenize_with(input, &options, |token| streamed.push(token)).unwrap();
+            let collected = tokenize(input, &options).unwrap();
+
+            let summary = |tokens: &[Token]| -> Vec<(TokenType, String, usize, usize)> {
+                tokens.iter().map(|t| (t.token_type, t.value.clone(), t.start, t.end)).collect()
+            };
+            assert_eq!(summary(&streamed), summary(&collected));
+        }
+    }
+
+    #[test]
+    fn test_expand_env() {
+        let options = CleanOptions {
+            expand_env: true,
+            env_vars: [("HOME".to_string(), "/home/ana".to_string())].into(),
+            ..CleanOptions::default()
+        };
+        assert_eq!(
+            clean_with_options(r#"{"home": "$HOME/data", $HOME: "${NOT_SET_ANYWHERE}"}"#, &options).unwrap(),
+            r#"{"home":"/home/ana/data","$HOME":"${NOT_SET_ANYWHERE}"}"#
+        );
+
+        let strict = CleanOptions { unset_env: UnsetEnvPolicy::Error, ..options };
+        let err = clean_with_options(r#"{"a": 1, "b": "$NOT_SET_ANYWHERE"}"#, &strict).unwrap_err();
+        assert_eq!((err.message.as_str(), err.position), ("Unset environment variable: NOT_SET_ANYWHERE", 14));
+    }
+
+    #[cfg(feature = "env")]
+    #[test]
+    fn test_expand_process_env() {
+        let options = CleanOptions { expand_env: true, ..CleanOptions::default() };
+        let expected = format!(r#"["{}"]"#, std::env::var("CARGO_PKG_NAME").unwrap());
+        assert_eq!(clean_with_options("['$CARGO_PKG_NAME']", &options).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_fill_width() {
+        let options = CleanOptions { fill_width: Some(24), ..CleanOptions::default() };
+        assert_eq!(
+            clean_with_options("{ids: [1, 2, 3], names: ['alice', 'bob', 'carol'],}", &options).unwrap(),
+            "{\n  \"ids\": [1, 2, 3],\n  \"names\": [\n    \"alice\",\n    \"bob\",\n    \"carol\"\n  ]\n}"
+        );
+        assert_eq!(clean_with_options("[1,2]", &options).unwrap(), "[1, 2]");
+
+        let mut handle = JsonCleanerHandle::with_options(options);
+        assert_eq!(handle.clean_str("[1,2,]").unwrap(), "[1, 2]");
+
+        // Stray top-level separators and closers are copied, not looped on
+        let options = CleanOptions { fill_width: Some(10), ..CleanOptions::default() };
+        assert_eq!(clean_with_options("1, 2", &options).unwrap(), "1,2");
+        assert_eq!(clean_with_options("]", &options).unwrap(), "]");
+        assert_eq!(clean_with_options("[1,2]]", &options).unwrap(), "[1, 2]]");
+    }
+
+    #[test]
+    fn test_columnar_option() {
+        let options = CleanOptions { columnar: true, ..CleanOptions::default() };
+        assert_eq!(
+            clean_with_options("[{a: 1, b: 'x'}, {a: 2, b: 'y',}]", &options).unwrap(),
+            r#"{"a":[1,2],"b":["x","y"]}"#
+        );
+        assert_eq!(clean_with_options("[{a: 1}, {b: 2}]", &options).unwrap(), r#"[{"a":1},{"b":2}]"#);
+
+        let mut handle = JsonCleanerHandle::with_options(options);
+        assert_eq!(handle.clean_str("[{a: 1}, {a: 2}]").unwrap(), r#"{"a":[1,2]}"#);
+
+        // JSON5 output isn't strict JSON; the error points into the output
+        let json5 = CleanOptions { columnar: true, dialect: Dialect::Json5, ..CleanOptions::default() };
+        let err = clean_with_options("[{a: 1}, {a: +2}]", &json5).unwrap_err();
+        assert_eq!(err.position, r#"[{"a":1},{"a":"#.len());
+    }
+
+    #[test]
+    fn test_drop_keys() {
+        let options = CleanOptions {
+            drop_keys: vec!["__proto__".into(), "constructor".into(), "secret_*".into()],
+            ..CleanOptions::default()
+        };
+        let clean = |input| clean_with_options(input, &options).unwrap();
+
+        assert_eq!(clean(r#"{"__proto__": {"isAdmin": true, "x": [1, {}]}, "a": 1}"#), r#"{"a":1}"#);
+        assert_eq!(
+            clean("{a: [{constructor: 1, b: 2}], secret_key: 'x', c: {__proto__: null}}"),
+            r#"{"a":[{"b":2}],"c":{}}"#
+        );
+        assert_eq!(clean(r#"["__proto__", {"secret": 1}]"#), r#"["__proto__",{"secret":1}]"#);
+        // Escaped spellings of a dropped key are dropped too
+        assert_eq!(clean(r#"{"\u005f_proto__": {"isAdmin": true}, "a": 1}"#), r#"{"a":1}"#);
+        assert_eq!(clean(r"{'constr\uctor': 1, 'secret\_x': 2}"), "{}");
+    }
+
+    #[test]
+    fn test_coerce_string_scalars() {
+        let options = CleanOptions { coerce_string_scalars: true, ..CleanOptions::default() };
+        let clean = |input| clean_with_options(input, &options).unwrap();
+
+        assert_eq!(clean(r#"{"a":"true","b":"42","c":"x"}"#), r#"{"a":true,"b":42,"c":"x"}"#);
+        assert_eq!(
+            clean(r#"{"42": "null", "d": ["-1.5e3", "42abc", " 42 ", "01"]}"#),
+            r#"{"42":null,"d":[-1.5e3,"42abc"," 42 ","01"]}"#
+        );
+    }
+
+    #[test]
+    fn test_identifier_values() {
+        let clean = |identifier_values| {
+            let options = CleanOptions { identifier_values, ..CleanOptions::default() };
+            clean_with_options("{type: active, ok: true}", &options)
+        };
+
+        assert_eq!(clean(IdentifierPolicy::QuoteAsString).unwrap(), r#"{"type":"active","ok":true}"#);
+        assert_eq!(clean(IdentifierPolicy::Null).unwrap(), r#"{"type":null,"ok":true}"#);
+        let err = clean(IdentifierPolicy::Error).unwrap_err();
+        assert_eq!(err.message, "Unexpected identifier: active");
+        assert_eq!(err.position, 7);
+    }
+
+    #[test]
+    fn test_bare_value_policy_alias() {
+        let clean = |policy: &str| {
+            let options: CleanOptions =
+                serde_json::from_str(&format!(r#"{{"bareValuePolicy": "{}"}}"#, policy)).unwrap();
+            clean_with_options("{a: hello}", &options)
+        };
+
+        assert_eq!(clean("quoteString").unwrap(), r#"{"a":"hello"}"#);
+        assert_eq!(clean("null").unwrap(), r#"{"a":null}"#);
+        let err = clean("error").unwrap_err();
+        assert_eq!((err.message.as_str(), err.position), ("Unexpected identifier: hello", 4));
+    }
+
+    #[test]
+    fn test_unwrap_keys() {
+        let options = CleanOptions {
+            unwrap_keys: vec!["data".into(), "result".into()],
+            ..CleanOptions::default()
+        };
+        let clean = |input| clean_with_options(input, &options).unwrap();
+
+        assert_eq!(clean(r#"{"data":{"x":1}}"#), r#"{"x":1}"#);
+        assert_eq!(clean("{data: {result: {value: 1,},}, }"), r#"{"value":1}"#);
+        assert_eq!(clean("[{data: [1, 2]}, {result: 3}]"), "[[1,2],3]");
+        // Other keys beside the wrapper key, or other keys alone, stay put
+        assert_eq!(clean(r#"{"data":{"x":1},"meta":2}"#), r#"{"data":{"x":1},"meta":2}"#);
+        assert_eq!(clean(r#"{"payload":{"data":[]}}"#), r#"{"payload":[]}"#);
+        assert_eq!(clean(r#"{"d\u0061ta":{"x":1}}"#), r#"{"x":1}"#);
+    }
+
+    #[test]
+    fn test_numeric_keys() {
+        let input = r#"{1: "a", 2: "b", 3.14: "pi", nested: {10: [1, 2]}}"#;
+        let result = clean_dirty_json_internal(input).unwrap();
+        assert_eq!(result, r#"{"1":"a","2":"b","3.14":"pi","nested":{"10":[1,2]}}"#);
+    }
+
     #[test]
     fn test_hex_numbers() {
         let input = r#"{"value": 0xFF}"#;
         let result = clean_dirty_json_internal(input).unwrap();
         assert_eq!(result, r#"{"value":255}"#);
     }
+
+    #[test]
+    fn test_json5_numbers_by_dialect() {
+        let input = "{a: +1, b: .5, c: 1., d: 0xFF}";
+
+        let lenient = clean_dirty_json_internal(input).unwrap();
+        assert_eq!(lenient, r#"{"a":1,"b":0.5,"c":1.0,"d":255}"#);
+
+        let options = CleanOptions { dialect: Dialect::Json5, ..Default::default() };
+        let json5 = clean_with_options(input, &options).unwrap();
+        assert_eq!(json5, r#"{"a":+1,"b":.5,"c":1.,"d":0xFF}"#);
+
+        let simd = two_stage::parse_two_stage(input, &options).unwrap();
+        assert_eq!(reconstruct_json(&simd, &options).unwrap(), json5);
+    }
+
+    #[test]
+    fn test_key_case() {
+        let snake = CleanOptions { key_case: KeyCase::Snake, ..Default::default() };
+        assert_eq!(clean_with_options("{FirstName: 1}", &snake).unwrap(), r#"{"first_name":1}"#);
+
+        let input = r#"{"userId": "KeepMe", nested: [{innerKey: 'Value'}]}"#;
+        let upper = CleanOptions { key_case: KeyCase::Upper, ..Default::default() };
+        assert_eq!(
+            clean_with_options(input, &upper).unwrap(),
+            r#"{"USERID":"KeepMe","NESTED":[{"INNERKEY":"Value"}]}"#
+        );
+    }
 }
@@ -0,0 +1,258 @@
+//! Dirty JSON linting
+//!
+//! The analysis counterpart to the cleaner: instead of repairing the input,
+//! every issue the cleaner would silently fix is reported with its span.
+//! Lexical errors (such as an unterminated string) stop the scan and are
+//! reported as a single `syntax` diagnostic.
+
+use std::collections::HashSet;
+
+use molt_core::*;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::keys::KeyTracker;
+use crate::options::CleanOptions;
+use crate::{ends_value, find_comment, starts_value, tokenize};
+
+/// Lint dirty JSON and return the diagnostics as a JSON array
+///
+/// Each entry is `{"severity", "code", "message", "span": {"start", "end"}}`
+/// with byte offsets into the input; see `lint_json`.
+#[wasm_bindgen]
+pub fn lint_dirty_json(input: &str) -> Result<String, JsValue> {
+    serde_json::to_string(&lint_json(input))
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// The input can't be repaired unambiguously
+    Error,
+    /// Not valid JSON, but the cleaner repairs it
+    Warning,
+}
+
+/// One issue found by `lint_json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Stable kebab-case identifier, e.g. `trailing-comma`
+    pub code: &'static str,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, code: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Self { severity, code, message: message.into(), span }
+    }
+}
+
+/// Report every structural issue in dirty JSON, sorted by position
+///
+/// Codes: `comment`, `single-quotes`, `unquoted-key`, `trailing-comma`,
+/// `missing-comma`, `duplicate-key`, `unbalanced-bracket` and `syntax`.
+pub fn lint_json(input: &str) -> Vec<Diagnostic> {
+    let options = CleanOptions::default();
+    let mut diagnostics = Vec::new();
+
+    let tokens = match tokenize(input, &options) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            let span = Span::new(e.position, e.position);
+            return vec![Diagnostic::new(Severity::Error, "syntax", e.message, span)];
+        }
+    };
+
+    let mut keys = KeyTracker::default();
+    let mut open: Vec<(TokenType, Span)> = Vec::new();
+    let mut seen_keys: Vec<Option<HashSet<&str>>> = Vec::new();
+    let mut prev: Option<TokenType> = None;
+    let mut gap_start = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let span = token.span();
+        let gap_end = token.start.max(gap_start);
+        push_comments(input, gap_start, gap_end, &options, &mut diagnostics);
+        gap_start = token.end;
+
+        if (keys.in_object() || keys.in_array())
+            && prev.is_some_and(ends_value)
+            && starts_value(token.token_type)
+        {
+            let at = Span::new(token.start, token.start);
+            diagnostics.push(Diagnostic::new(Severity::Warning, "missing-comma", "Missing comma", at));
+            keys.observe(TokenType::Comma);
+        }
+
+        let is_key = keys.is_key();
+        match token.token_type {
+            TokenType::String if span.slice(input).starts_with('\'') => {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning,
+                    "single-quotes",
+                    "String uses single quotes",
+                    span,
+                ));
+            }
+            TokenType::Identifier | TokenType::Number if is_key => {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning,
+                    "unquoted-key",
+                    format!("Unquoted key: {}", token.value),
+                    span,
+                ));
+            }
+            TokenType::Comma => {
+                let next = tokens.get(i + 1).map(|t| t.token_type);
+                if matches!(next, Some(TokenType::RightBrace | TokenType::RightBracket)) {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Warning,
+                        "trailing-comma",
+                        "Trailing comma",
+                        span,
+                    ));
+                }
+            }
+            TokenType::LeftBrace | TokenType::LeftBracket => {
+                open.push((token.token_type, span));
+                let keys_seen = (token.token_type == TokenType::LeftBrace).then(HashSet::new);
+                seen_keys.push(keys_seen);
+            }
+            TokenType::RightBrace | TokenType::RightBracket => {
+                let opener = if token.token_type == TokenType::RightBrace {
+                    TokenType::LeftBrace
+                } else {
+                    TokenType::LeftBracket
+                };
+                if let Some(depth) = open.iter().rposition(|&(t, _)| t == opener) {
+                    // Containers opened after the matching bracket were never closed
+                    for (_, unclosed) in open.drain(depth + 1..).rev() {
+                        diagnostics.push(Diagnostic::new(
+                            Severity::Error,
+                            "unbalanced-bracket",
+                            format!("Unclosed '{}'", unclosed.slice(input)),
+                            unclosed,
+                        ));
+                    }
+                    open.pop();
+                    seen_keys.truncate(depth);
+                } else {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Error,
+                        "unbalanced-bracket",
+                        format!("Unexpected '{}'", span.slice(input)),
+                        span,
+                    ));
+                    prev = Some(token.token_type);
+                    continue;
+                }
+            }
+            TokenType::EOF => {
+                for &(_, span) in open.iter().rev() {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Error,
+                        "unbalanced-bracket",
+                        format!("Unclosed '{}'", span.slice(input)),
+                        span,
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        let is_key_token = matches!(
+            token.token_type,
+            TokenType::String | TokenType::Identifier | TokenType::Number
+        );
+        if is_key && is_key_token {
+            if let Some(Some(seen)) = seen_keys.last_mut() {
+                if !seen.insert(token.value.as_str()) {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Warning,
+                        "duplicate-key",
+                        format!("Duplicate key: {}", token.value),
+                        span,
+                    ));
+                }
+            }
+        }
+
+        keys.observe(token.token_type);
+        prev = Some(token.token_type);
+    }
+
+    diagnostics.sort_by_key(|d| d.span.start);
+    diagnostics
+}
+
+/// Report each comment in the gap `input[start..end]`
+fn push_comments(
+    input: &str,
+    start: usize,
+    end: usize,
+    options: &CleanOptions,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let gap = &input[start..end];
+    let bytes = gap.as_bytes();
+    let mut pos = 0;
+
+    while let Some(offset) = find_comment(&bytes[pos..], options.comment_styles) {
+        let comment_start = pos + offset;
+        let comment_end = if bytes[comment_start] == b'/' && bytes[comment_start + 1] == b'*' {
+            gap[comment_start + 2..]
+                .find("*/")
+                .map_or(gap.len(), |close| comment_start + 2 + close + 2)
+        } else {
+            gap[comment_start..].find('\n').map_or(gap.len(), |newline| comment_start + newline)
+        };
+        diagnostics.push(Diagnostic::new(
+            Severity::Warning,
+            "comment",
+            "Comments are not allowed in JSON",
+            Span::new(start + comment_start, start + comment_end),
+        ));
+        pos = comment_end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_three_issues() {
+        let input = "{a: 1, 'b': 2,}";
+        let diagnostics = lint_json(input);
+        let found: Vec<(&str, Span)> = diagnostics.iter().map(|d| (d.code, d.span)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("unquoted-key", Span::new(1, 2)),
+                ("single-quotes", Span::new(7, 10)),
+                ("trailing-comma", Span::new(13, 14)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_structure_issues() {
+        let input = "// note\n{\"a\": 1 \"a\": [2, 3}";
+        let codes: Vec<(&str, Severity)> =
+            lint_json(input).iter().map(|d| (d.code, d.severity)).collect();
+        assert_eq!(
+            codes,
+            vec![
+                ("comment", Severity::Warning),
+                ("missing-comma", Severity::Warning),
+                ("duplicate-key", Severity::Warning),
+                ("unbalanced-bracket", Severity::Error),
+            ]
+        );
+        assert_eq!(lint_json(r#"{"a": [1, 2]}"#), vec![]);
+    }
+}
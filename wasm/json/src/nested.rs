@@ -0,0 +1,135 @@
+//! Inlining of JSON documents embedded in string values
+//!
+//! Logs often store JSON inside a JSON string, sometimes re-serialized
+//! several times (`"{\\\"a\\\":1}"`). Each decoding layer costs one level
+//! of `nested_json_max_depth`, so a depth of 1 unwraps exactly one level.
+//! With `NestedJsonMode::Unescape` the value stays a string and only its
+//! outermost extra layer of escaping is removed.
+
+use molt_core::unescape_json_string;
+
+use crate::options::{CleanOptions, NestedJsonMode};
+use crate::clean_with_options;
+
+/// Rewrite a string value that holds a JSON object or array
+///
+/// Returns the text that replaces the string token: the cleaned document
+/// with `NestedJsonMode::Inline`, or the string with one layer of escaping
+/// removed with `NestedJsonMode::Unescape`.
+pub fn rewrite_nested_json(raw: &str, options: &CleanOptions) -> Option<String> {
+    let mut layers = decode_layers(raw, options.nested_json_max_depth)?;
+    match options.nested_json_mode {
+        NestedJsonMode::Inline => {
+            // Whole-document passes apply once, to the outer document
+            let inner = CleanOptions {
+                nested_json_max_depth: options.nested_json_max_depth - layers.len(),
+                columnar: false,
+                fill_width: None,
+                ..options.clone()
+            };
+            clean_with_options(layers.last()?, &inner).ok()
+        }
+        NestedJsonMode::Unescape if layers.len() > 1 => serde_json::to_string(&layers.swap_remove(1)).ok(),
+        NestedJsonMode::Unescape => None,
+    }
+}
+
+/// Decode the string value until it parses as a JSON object or array
+///
+/// Returns every decoded layer, the document last, or `None` if no object
+/// or array is reached within `max_depth` layers.
+fn decode_layers(raw: &str, max_depth: usize) -> Option<Vec<String>> {
+    let mut layers = vec![decode_string_value(raw)?];
+
+    while layers.len() <= max_depth {
+        let content = layers.last()?;
+        let trimmed = content.trim_start();
+        if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+            return None;
+        }
+
+        match serde_json::from_str::<serde_json::Value>(content) {
+            Ok(value) if value.is_object() || value.is_array() => return Some(layers),
+            // Over-escaped: peel another layer of escaping
+            _ if content.contains("\\\"") => {
+                let next = decode_string_value(content)?;
+                layers.push(next);
+            }
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Decode the escape sequences of a raw string token value
+fn decode_string_value(raw: &str) -> Option<String> {
+    unescape_json_string(raw).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(depth: usize) -> CleanOptions {
+        CleanOptions {
+            parse_nested_json_strings: true,
+            nested_json_max_depth: depth,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inline_nested_object() {
+        let input = r#"{"payload":"{\"x\":1}", "text": "[not json"}"#;
+        let result = clean_with_options(input, &options(4)).unwrap();
+        assert_eq!(result, r#"{"payload":{"x":1},"text":"[not json"}"#);
+    }
+
+    #[test]
+    fn test_over_escaped_depth_limit() {
+        let input = r#"{"p":"{\\\"a\\\":\\\"{\\\\\\\"b\\\\\\\":2}\\\"}"}"#;
+        let result = clean_with_options(input, &options(1)).unwrap();
+        assert_eq!(result, input);
+
+        let result = clean_with_options(input, &options(2)).unwrap();
+        assert_eq!(result, r#"{"p":{"a":"{\"b\":2}"}}"#);
+
+        let result = clean_with_options(input, &options(4)).unwrap();
+        assert_eq!(result, r#"{"p":{"a":{"b":2}}}"#);
+    }
+
+    #[test]
+    fn test_fill_width_applies_to_outer_document() {
+        let narrow = CleanOptions { fill_width: Some(20), ..options(4) };
+        let input = r#"{"payload": "{\"alpha\": [1,2,3,4,5,6,7,8], \"beta\": {\"x\": 1}}", "k": 1}"#;
+        assert_eq!(
+            clean_with_options(input, &narrow).unwrap(),
+            "{\n  \"payload\": {\n    \"alpha\": [\n      1,\n      2,\n      3,\n      4,\n      5,\n      6,\n      7,\n      8\n    ],\n    \"beta\": {\"x\": 1}\n  },\n  \"k\": 1\n}"
+        );
+
+        let wide = CleanOptions { fill_width: Some(80), ..options(4) };
+        let result = clean_with_options(r#"{"p": "{\"a\": 1}"}"#, &wide).unwrap();
+        assert_eq!(result, r#"{"p": {"a": 1}}"#);
+    }
+
+    #[test]
+    fn test_columnar_applies_to_outer_document() {
+        let columnar = CleanOptions { columnar: true, ..options(4) };
+        let input = r#"[{"a": "[{\"x\":1},{\"x\":2}]"}, {"a": "[]"}]"#;
+        let result = clean_with_options(input, &columnar).unwrap();
+        assert_eq!(result, r#"{"a":[[{"x":1},{"x":2}],[]]}"#);
+    }
+
+    #[test]
+    fn test_unescape_mode() {
+        let unescape = CleanOptions { nested_json_mode: NestedJsonMode::Unescape, ..options(4) };
+
+        let input = r#"{"p":"{\\\"a\\\":1}","q":"{\"x\":1}","r":"{\\\"note"}"#;
+        let result = clean_with_options(input, &unescape).unwrap();
+        assert_eq!(result, r#"{"p":"{\"a\":1}","q":"{\"x\":1}","r":"{\\\"note"}"#);
+
+        let result = clean_with_options(input, &options(4)).unwrap();
+        assert_eq!(result, r#"{"p":{"a":1},"q":{"x":1},"r":"{\\\"note"}"#);
+    }
+}
@@ -0,0 +1,318 @@
+//! Number literal scanning shared by the scalar and two-stage tokenizers
+
+use molt_core::*;
+use crate::options::{BigNumberMode, CleanOptions, Dialect, OverflowPolicy};
+use crate::simd::number_run_length;
+
+/// Scan a number literal starting at `start`, stopping before `end`
+///
+/// Returns the token type, the token value and the position just past the literal.
+/// In the lenient dialect the value is normalized to strict JSON
+/// (leading `+` dropped, bare decimal points padded, hex converted to decimal);
+/// hex values too large for `u64` become strings according to `big_number_mode`.
+/// In the JSON5 dialect the source text is kept verbatim. In the strict
+/// dialect anything but a JSON number literal is an error.
+pub fn scan_number(
+    input: &[u8],
+    start: usize,
+    end: usize,
+    options: &CleanOptions,
+) -> Result<(TokenType, String, usize), ParseError> {
+    let mut pos = start;
+
+    // Leading +
+    if input[pos] == b'+' {
+        if options.dialect == Dialect::Strict {
+            return Err(ParseError::new("Leading '+' is not allowed in JSON numbers", pos));
+        }
+        pos += 1;
+        if pos >= end {
+            return Err(ParseError::new("Unexpected end after +", pos));
+        }
+    }
+    let unsigned_start = pos;
+
+    // Hex numbers (optionally negative)
+    let mut hex_pos = pos;
+    if hex_pos < end && input[hex_pos] == b'-' {
+        hex_pos += 1;
+    }
+    if hex_pos + 1 < end && input[hex_pos] == b'0' && matches!(input[hex_pos + 1], b'x' | b'X') {
+        pos = hex_pos + 2;
+        let hex_start = pos;
+        while pos < end && input[pos].is_ascii_hexdigit() {
+            pos += 1;
+        }
+
+        if pos == hex_start {
+            return Err(ParseError::new("Invalid hex number", start));
+        }
+
+        let value = match options.dialect {
+            Dialect::Json5 => ascii_slice(input, start, pos),
+            Dialect::Strict => {
+                return Err(ParseError::new("Hex numbers are not allowed in JSON", start));
+            }
+            Dialect::Lenient => {
+                let digits = ascii_slice(input, hex_start, pos);
+                let sign = if hex_pos > unsigned_start { "-" } else { "" };
+                // Convert hex to decimal
+                match u64::from_str_radix(&digits, 16) {
+                    Ok(hex_val) => format!("{}{}", sign, hex_val),
+                    Err(_) => {
+                        let value = match options.big_number_mode {
+                            BigNumberMode::DecimalString => {
+                                format!("{}{}", sign, hex_to_decimal(&digits))
+                            }
+                            BigNumberMode::HexString => ascii_slice(input, unsigned_start, pos),
+                        };
+                        return Ok((TokenType::String, value, pos));
+                    }
+                }
+            }
+        };
+        return Ok((TokenType::Number, value, pos));
+    }
+
+    // Regular numbers (SIMD-assisted run scan)
+    pos += number_run_length(input, pos, end);
+
+    let value = match options.dialect {
+        Dialect::Json5 => ascii_slice(input, start, pos),
+        Dialect::Lenient => normalize_decimal_points(&input[unsigned_start..pos]),
+        Dialect::Strict => {
+            let value = ascii_slice(input, start, pos);
+            if !is_json_number(&value) {
+                return Err(ParseError::new(format!("Invalid JSON number: {}", value), start));
+            }
+            value
+        }
+    };
+    Ok((TokenType::Number, value, pos))
+}
+
+/// Convert hex digits of any length to decimal digits
+fn hex_to_decimal(hex: &str) -> String {
+    // Little-endian limbs in base 10^9
+    const BASE: u64 = 1_000_000_000;
+    let mut limbs: Vec<u64> = vec![0];
+
+    for digit in hex.chars().filter_map(|c| c.to_digit(16)) {
+        let mut carry = u64::from(digit);
+        for limb in limbs.iter_mut() {
+            let value = *limb * 16 + carry;
+            *limb = value % BASE;
+            carry = value / BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+    }
+
+    let mut out = limbs.last().map(|l| l.to_string()).unwrap_or_default();
+    for limb in limbs.iter().rev().skip(1) {
+        out.push_str(&format!("{:09}", limb));
+    }
+    out
+}
+
+/// Whether a scanned number overflows to a non-finite `f64` (e.g. `1e400`)
+pub fn is_out_of_range(value: &str) -> bool {
+    value.parse::<f64>().is_ok_and(|n| !n.is_finite())
+}
+
+/// Reject out-of-range numbers when the policy asks for an error
+pub fn check_range(value: &str, start: usize, policy: OverflowPolicy) -> Result<(), ParseError> {
+    if policy == OverflowPolicy::Error && is_out_of_range(value) {
+        return Err(ParseError::new(format!("Number out of range: {}", value), start));
+    }
+    Ok(())
+}
+
+/// Round a fractional or exponent number literal to `digits` significant digits
+///
+/// Returns `None` for integers, non-finite or unparsable values (such as hex),
+/// and when the rounded text would not be shorter than `value`. Literals with
+/// an exponent keep exponent form; others are written as plain decimals.
+pub fn round_significant(value: &str, digits: u32) -> Option<String> {
+    let has_exponent = value.contains(['e', 'E']);
+    if !has_exponent && !value.contains('.') {
+        return None;
+    }
+    let number = value.parse::<f64>().ok().filter(|n| n.is_finite())?;
+
+    let precision = digits.max(1) as usize - 1;
+    let rounded: f64 = format!("{:.*e}", precision, number).parse().ok()?;
+    let text = if has_exponent { format!("{:e}", rounded) } else { rounded.to_string() };
+    (text.len() < value.len()).then_some(text)
+}
+
+/// Canonical form of a decimal number literal
+///
+/// Drops leading zeros from the integer part (`007` → `7`), insignificant
+/// trailing fraction zeros (`1.2000` → `1.2`) and a fraction left empty
+/// (`1.` and `1.0` → `1`). The exponent is kept as written. Returns `None`
+/// for literals that aren't decimal numbers (such as hex) or are already
+/// canonical.
+pub fn canonical_number(value: &str) -> Option<String> {
+    let (mantissa, exponent) = match value.find(['e', 'E']) {
+        Some(at) => value.split_at(at),
+        None => (value, ""),
+    };
+    let (sign, unsigned) = match mantissa.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", mantissa),
+    };
+    let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) || int.len() + frac.len() == 0 {
+        return None;
+    }
+
+    let int = int.trim_start_matches('0');
+    let frac = frac.trim_end_matches('0');
+    let mut text = format!("{}{}", sign, if int.is_empty() { "0" } else { int });
+    if !frac.is_empty() {
+        text.push('.');
+        text.push_str(frac);
+    }
+    text.push_str(exponent);
+    (text != value).then_some(text)
+}
+
+/// Whether an integer literal has a leading zero, e.g. `007` or `-01`
+pub fn has_leading_zero(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    digits.len() > 1 && digits.starts_with('0') && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Pad bare decimal points so `.5` becomes `0.5` and `1.` becomes `1.0`
+fn normalize_decimal_points(text: &[u8]) -> String {
+    let mut value = String::with_capacity(text.len() + 2);
+
+    for (i, &byte) in text.iter().enumerate() {
+        if byte == b'.' {
+            let digit_before = i > 0 && text[i - 1].is_ascii_digit();
+            let digit_after = i + 1 < text.len() && text[i + 1].is_ascii_digit();
+            if !digit_before {
+                value.push('0');
+            }
+            value.push('.');
+            if !digit_after {
+                value.push('0');
+            }
+        } else {
+            value.push(byte as char);
+        }
+    }
+
+    value
+}
+
+/// Copy an ASCII byte range into a String
+#[inline]
+fn ascii_slice(input: &[u8], start: usize, end: usize) -> String {
+    input[start..end].iter().map(|&b| b as char).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_number() {
+        assert_eq!(canonical_number("1.2000").as_deref(), Some("1.2"));
+        assert_eq!(canonical_number("007").as_deref(), Some("7"));
+        assert_eq!(canonical_number("1.").as_deref(), Some("1"));
+        assert_eq!(canonical_number("-0.0").as_deref(), Some("-0"));
+        assert_eq!(canonical_number("1.500e10").as_deref(), Some("1.5e10"));
+        assert_eq!(canonical_number("0"), None);
+        assert_eq!(canonical_number("100"), None);
+        assert_eq!(canonical_number("2e-10"), None);
+        assert_eq!(canonical_number("0xFF"), None);
+    }
+
+    fn scan(input: &str, dialect: Dialect) -> String {
+        let options = CleanOptions { dialect, ..CleanOptions::default() };
+        scan_number(input.as_bytes(), 0, input.len(), &options).unwrap().1
+    }
+
+    #[test]
+    fn test_lenient_normalizes() {
+        assert_eq!(scan("+1", Dialect::Lenient), "1");
+        assert_eq!(scan(".5", Dialect::Lenient), "0.5");
+        assert_eq!(scan("-.5", Dialect::Lenient), "-0.5");
+        assert_eq!(scan("1.", Dialect::Lenient), "1.0");
+        assert_eq!(scan("0xFF", Dialect::Lenient), "255");
+        assert_eq!(scan("-0x10", Dialect::Lenient), "-16");
+        assert_eq!(scan("1.5e10", Dialect::Lenient), "1.5e10");
+    }
+
+    #[test]
+    fn test_json5_preserves() {
+        assert_eq!(scan("+1", Dialect::Json5), "+1");
+        assert_eq!(scan(".5", Dialect::Json5), ".5");
+        assert_eq!(scan("1.", Dialect::Json5), "1.");
+        assert_eq!(scan("0xFF", Dialect::Json5), "0xFF");
+    }
+
+    #[test]
+    fn test_leading_plus_by_dialect() {
+        assert_eq!(scan("+5", Dialect::Lenient), "5");
+        assert_eq!(scan("+5", Dialect::Json5), "+5");
+
+        let strict = CleanOptions { dialect: Dialect::Strict, ..CleanOptions::default() };
+        let err = scan_number(b"[+5]", 1, 4, &strict).unwrap_err();
+        assert_eq!((err.message.as_str(), err.position), ("Leading '+' is not allowed in JSON numbers", 1));
+        assert_eq!(scan("-5.5e3", Dialect::Strict), "-5.5e3");
+        for invalid in [".5", "1.", "0xFF", "007"] {
+            assert!(scan_number(invalid.as_bytes(), 0, invalid.len(), &strict).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert!(is_out_of_range("1e400"));
+        assert!(is_out_of_range("-1e400"));
+        assert!(!is_out_of_range("1e300"));
+        assert!(!is_out_of_range("0xFF"));
+        assert!(check_range("1e400", 0, OverflowPolicy::Keep).is_ok());
+        assert!(check_range("1e400", 0, OverflowPolicy::Error).is_err());
+    }
+
+    #[test]
+    fn test_round_significant() {
+        let rounded: Vec<_> = ["3.14159265", "-0.000123456", "2.71828e10", "98765.4321", "1.5", "42", "0x1F"]
+            .iter()
+            .map(|value| round_significant(value, 3))
+            .collect();
+        assert_eq!(
+            rounded,
+            [
+                Some("3.14".to_string()),
+                Some("-0.000123".to_string()),
+                Some("2.72e10".to_string()),
+                Some("98800".to_string()),
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_hex() {
+        assert!(scan_number(b"0x", 0, 2, &CleanOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_big_hex() {
+        let input = b"0xFFFFFFFFFFFFFFFFFFFF";
+        let (token_type, value, _) = scan_number(input, 0, input.len(), &CleanOptions::default()).unwrap();
+        assert_eq!(token_type, TokenType::String);
+        assert_eq!(value, "1208925819614629174706175");
+
+        let hex = CleanOptions { big_number_mode: BigNumberMode::HexString, ..CleanOptions::default() };
+        assert_eq!(scan_number(input, 0, input.len(), &hex).unwrap().1, "0xFFFFFFFFFFFFFFFFFFFF");
+        assert_eq!(hex_to_decimal("10000000000000000"), "18446744073709551616");
+    }
+}
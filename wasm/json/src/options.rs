@@ -0,0 +1,387 @@
+//! Cleaner configuration
+//!
+//! Options that control how dirty JSON is tokenized and reconstructed.
+//! Every option defaults to the behavior of the plain `clean_dirty_json` entry point.
+
+use std::collections::HashMap;
+
+use molt_core::CommentStyles;
+use serde::Deserialize;
+
+#[cfg(feature = "unicode-normalize")]
+use crate::unicode::NormForm;
+
+/// Output dialect produced by the cleaner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Dialect {
+    /// Repair dirty input into strict JSON (default)
+    #[default]
+    Lenient,
+    /// Keep JSON5 lexical forms such as `+1`, `.5`, `1.` and `0xFF` as written
+    Json5,
+    /// Reject number literals that aren't valid JSON (`+1`, `.5`, `1.`,
+    /// `0xFF`) instead of repairing them
+    Strict,
+}
+
+/// Case applied to object keys during reconstruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyCase {
+    /// Leave keys unchanged (default)
+    #[default]
+    AsIs,
+    Lower,
+    Upper,
+    Snake,
+    Camel,
+}
+
+/// Handling of elided array elements such as `[1,,2]` or `[,1]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HolePolicy {
+    /// Fill holes with `null`, matching JavaScript sparse array serialization (default)
+    #[default]
+    Null,
+    /// Remove holes entirely
+    Drop,
+}
+
+/// Handling of numbers that overflow to a non-finite `f64`, such as `1e400`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverflowPolicy {
+    /// Emit the literal unchanged (default)
+    #[default]
+    Keep,
+    /// Replace the number with `null`
+    Null,
+    /// Fail with a parse error
+    Error,
+}
+
+/// Object entries removed during reconstruction
+///
+/// Empty strings include strings that are entirely whitespace. Array
+/// elements are never dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DropPolicy {
+    /// Keep every entry (default)
+    #[default]
+    Keep,
+    /// Drop entries whose value is `null`
+    DropNullValues,
+    /// Drop entries whose value is an empty string
+    DropEmptyStrings,
+    /// Drop both
+    DropBoth,
+}
+
+/// What `parse_nested_json_strings` does with a string holding JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NestedJsonMode {
+    /// Replace the string with the object or array it holds (default)
+    #[default]
+    Inline,
+    /// Keep the string but remove one layer of over-escaping, so
+    /// `"{\\\"a\\\":1}"` becomes `"{\"a\":1}"`; strings that already hold
+    /// plain JSON are left as written
+    Unescape,
+}
+
+/// Handling of bare identifiers in value position, such as `active` in `{type: active}`
+///
+/// `true`, `false` and `null` are keywords and never affected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IdentifierPolicy {
+    /// Quote the identifier as a string (default)
+    #[default]
+    #[serde(alias = "quoteString")]
+    QuoteAsString,
+    /// Replace the identifier with `null`
+    Null,
+    /// Fail with a parse error
+    Error,
+}
+
+/// Handling of `$VAR` references to unset variables with `expand_env`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnsetEnvPolicy {
+    /// Leave the reference as written (default)
+    #[default]
+    Keep,
+    /// Fail with a parse error naming the variable
+    Error,
+}
+
+/// Representation of hex literals too large for `u64`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BigNumberMode {
+    /// Exact decimal digits as a JSON string (default)
+    #[default]
+    DecimalString,
+    /// The hex literal as written, as a JSON string
+    HexString,
+}
+
+/// Separators accepted between an object key and its value
+///
+/// Whatever separator appears in the input, the output always uses `:`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeySeparators(u8);
+
+impl KeySeparators {
+    /// `a: 1`
+    pub const COLON: Self = Self(1);
+    /// `a => 1` (Ruby hashes)
+    pub const FAT_ARROW: Self = Self(1 << 1);
+    /// `a = 1` (config formats)
+    pub const EQUALS: Self = Self(1 << 2);
+    /// All supported separators
+    pub const ALL: Self = Self(0b111);
+
+    /// Whether every separator in `other` is accepted
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Combine two separator sets
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl Default for KeySeparators {
+    /// Colon only, as in JSON
+    fn default() -> Self {
+        Self::COLON
+    }
+}
+
+impl std::ops::BitOr for KeySeparators {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeySeparators {
+    /// Deserialize from a list of separator names: `"colon"`, `"fatArrow"`, `"equals"`
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let names = Vec::<String>::deserialize(deserializer)?;
+        names.iter().try_fold(Self(0), |separators, name| {
+            let separator = match name.as_str() {
+                "colon" => Self::COLON,
+                "fatArrow" => Self::FAT_ARROW,
+                "equals" => Self::EQUALS,
+                other => {
+                    return Err(serde::de::Error::unknown_variant(
+                        other,
+                        &["colon", "fatArrow", "equals"],
+                    ))
+                }
+            };
+            Ok(separators | separator)
+        })
+    }
+}
+
+/// Options for the dirty JSON cleaner
+///
+/// Deserializes from a JS-style object with camelCase field names;
+/// unspecified fields use their defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CleanOptions {
+    /// Output dialect
+    pub dialect: Dialect,
+    /// Case conversion for object keys; string values are never touched
+    pub key_case: KeyCase,
+    /// Replace invalid UTF-8 inside strings with U+FFFD instead of failing
+    /// (byte input only, e.g. `clean_bytes_with_options`)
+    pub lossy_utf8: bool,
+    /// Comment syntaxes stripped from the input (default: `//` and `/* */`)
+    pub comment_styles: CommentStyles,
+    /// Fail on the first comment instead of stripping it
+    pub reject_comments: bool,
+    /// How elided array elements are repaired
+    pub array_holes: HolePolicy,
+    /// Inline string values that contain a JSON object or array
+    pub parse_nested_json_strings: bool,
+    /// Maximum number of string-decoding layers unwrapped when inlining
+    /// nested JSON (default: 4; 1 unwraps exactly one level)
+    pub nested_json_max_depth: usize,
+    /// Whether nested JSON is inlined or only unescaped by one level
+    pub nested_json_mode: NestedJsonMode,
+    /// Separators accepted between keys and values (default: `:` only)
+    pub key_separators: KeySeparators,
+    /// Treat unquoted `${VAR}` and `{{mustache}}` placeholders as string values
+    pub preserve_placeholders: bool,
+    /// Capture an unquoted value after `:` verbatim up to the next comma,
+    /// closing bracket or line break and quote it, so `path: /usr/bin` keeps
+    /// its slashes. Single numbers, keywords and identifiers are unaffected.
+    pub unquoted_value_until_delimiter: bool,
+    /// Rewrite `\r\n` and `\r` line breaks inside string values as `\n`
+    pub normalize_newlines: bool,
+    /// How numbers outside the `f64` range are handled
+    pub overflow_numbers: OverflowPolicy,
+    /// Wrap a bare top-level value sequence such as `1, 2, 3` in `[...]`
+    pub auto_array: bool,
+    /// Skip a leading anti-XSSI prefix (`)]}'`, `while(1);`, `for(;;);`) or `#!` line
+    pub strip_xssi: bool,
+    /// How hex literals that overflow `u64` are emitted (lenient dialect only)
+    pub big_number_mode: BigNumberMode,
+    /// Object entries with null or empty values to remove
+    pub drop_empty: DropPolicy,
+    /// How bare identifier values are handled; `bareValuePolicy` is
+    /// accepted as an alias in options objects
+    #[serde(alias = "bareValuePolicy")]
+    pub identifier_values: IdentifierPolicy,
+    /// Object keys whose entries are removed at any depth; `*` in a pattern
+    /// matches any run of characters (e.g. `__proto__`, `secret_*`)
+    pub drop_keys: Vec<String>,
+    /// Emit string values that are exactly `true`, `false`, `null` or a JSON
+    /// number within `f64` range as the unquoted literal (keys are never
+    /// coerced); see `molt_core::classify_scalar`
+    pub coerce_string_scalars: bool,
+    /// Decode escapes JSON doesn't require (`\/`, `\uXXXX` for printable
+    /// characters) in strings
+    pub minimize_escapes: bool,
+    /// Write every non-ASCII character in strings as a `\uXXXX` escape, with
+    /// surrogate pairs above U+FFFF, like Python's `ensure_ascii`; takes
+    /// precedence over `minimize_escapes` for non-ASCII characters
+    pub ascii_safe: bool,
+    /// Insert a comma between adjacent values (or a value and the next key)
+    /// inside a container, e.g. `[1 2]` or entries separated only by newlines
+    pub insert_missing_commas: bool,
+    /// Round fractional and exponent numbers to this many significant
+    /// digits; integers and values already that short are unchanged
+    pub round_floats: Option<u32>,
+    /// Write numbers in canonical form: no leading zeros on the integer part
+    /// (`007` → `7`), no trailing fraction zeros (`1.2000` → `1.2`) and no
+    /// empty fraction (`1.` → `1`); exponents are kept as written
+    pub normalize_number_format: bool,
+    /// With `normalize_number_format`, emit integers with leading zeros as
+    /// strings (`007` → `"007"`) instead of stripping the zeros, e.g. for
+    /// zip codes or identifiers
+    pub leading_zero_strings: bool,
+    /// Insert a colon between an object key and a value that directly
+    /// follows it, e.g. `{name "alice"}`
+    pub insert_missing_colons: bool,
+    /// Initial token buffer capacity; defaults to a size-based estimate
+    pub token_capacity: Option<usize>,
+    /// Initial output capacity; defaults to an estimate from the tokens
+    pub output_capacity: Option<usize>,
+    /// Measure the cleaned output in a first pass and allocate it exactly,
+    /// trading a second walk over the tokens for no reallocations; ignored
+    /// with `columnar` or `fill_width`, which rewrite the output afterwards
+    pub exact_output_size: bool,
+    /// Transpose a top-level array of objects with identical keys into an
+    /// object of column arrays, e.g. `{"a":[1,3],"b":[2,4]}`
+    pub columnar: bool,
+    /// Replace an object whose only entry has one of these keys with that
+    /// entry's value, e.g. `{"data": {"x": 1}}` becomes `{"x": 1}`; `*` in a
+    /// pattern matches any run of characters
+    pub unwrap_keys: Vec<String>,
+    /// Pretty-print the output, keeping each array or object on one line
+    /// when it fits in this many columns and breaking it one entry per line
+    /// (two-space indent) otherwise
+    pub fill_width: Option<usize>,
+    /// Replace `$VAR` and `${VAR}` in string values (not keys) with
+    /// variable values from `env_vars`, then (with the `env` feature) from
+    /// the process environment
+    pub expand_env: bool,
+    /// Variable values for `expand_env`; the only source in WASM builds
+    pub env_vars: HashMap<String, String>,
+    /// What `expand_env` does with references to unset variables
+    pub unset_env: UnsetEnvPolicy,
+    /// Unicode normalization applied to decoded string values
+    #[cfg(feature = "unicode-normalize")]
+    pub unicode_normalize: Option<NormForm>,
+    /// Also apply `unicode_normalize` to object keys
+    #[cfg(feature = "unicode-normalize")]
+    pub unicode_normalize_keys: bool,
+}
+
+impl Default for CleanOptions {
+    fn default() -> Self {
+        Self {
+            dialect: Dialect::default(),
+            key_case: KeyCase::default(),
+            lossy_utf8: false,
+            comment_styles: CommentStyles::default(),
+            reject_comments: false,
+            array_holes: HolePolicy::default(),
+            parse_nested_json_strings: false,
+            nested_json_max_depth: 4,
+            nested_json_mode: NestedJsonMode::default(),
+            key_separators: KeySeparators::default(),
+            preserve_placeholders: false,
+            unquoted_value_until_delimiter: false,
+            normalize_newlines: false,
+            overflow_numbers: OverflowPolicy::default(),
+            auto_array: false,
+            strip_xssi: false,
+            big_number_mode: BigNumberMode::default(),
+            drop_empty: DropPolicy::default(),
+            identifier_values: IdentifierPolicy::default(),
+            drop_keys: Vec::new(),
+            coerce_string_scalars: false,
+            minimize_escapes: false,
+            ascii_safe: false,
+            insert_missing_commas: false,
+            round_floats: None,
+            normalize_number_format: false,
+            leading_zero_strings: false,
+            insert_missing_colons: false,
+            token_capacity: None,
+            output_capacity: None,
+            exact_output_size: false,
+            columnar: false,
+            unwrap_keys: Vec::new(),
+            fill_width: None,
+            expand_env: false,
+            env_vars: HashMap::new(),
+            unset_env: UnsetEnvPolicy::default(),
+            #[cfg(feature = "unicode-normalize")]
+            unicode_normalize: None,
+            #[cfg(feature = "unicode-normalize")]
+            unicode_normalize_keys: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_partial() {
+        let options: CleanOptions = serde_json::from_str(
+            r#"{"keyCase": "snake", "commentStyles": ["hash"], "keySeparators": ["colon", "fatArrow"], "dropKeys": ["__proto__"]}"#,
+        )
+        .unwrap();
+        assert_eq!(options.key_case, KeyCase::Snake);
+        assert_eq!(options.comment_styles, CommentStyles::HASH);
+        assert!(options.key_separators.contains(KeySeparators::FAT_ARROW));
+        assert_eq!(options.drop_keys, vec!["__proto__"]);
+
+        let defaults = CleanOptions::default();
+        assert_eq!(options.dialect, defaults.dialect);
+        assert_eq!(options.nested_json_max_depth, 4);
+        assert_eq!(options.nested_json_mode, NestedJsonMode::Inline);
+        assert_eq!(options.identifier_values, defaults.identifier_values);
+
+        assert!(serde_json::from_str::<CleanOptions>(r#"{"commentStyles": ["semicolon"]}"#).is_err());
+    }
+}
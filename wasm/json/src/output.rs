@@ -0,0 +1,140 @@
+//! Output sinks for reconstruction
+//!
+//! `reconstruct_json_into` writes through `Output` so the same walk can
+//! either build the cleaned string or, with `SizeCounter`, only measure it.
+//! Measuring first lets `exact_output_size` allocate the result once.
+//! `SliceOutput` streams straight into a caller-provided byte buffer.
+
+use molt_core::push_escaped_char;
+
+/// Destination for reconstructed JSON text
+pub(crate) trait Output {
+    fn push(&mut self, c: char);
+    fn push_str(&mut self, s: &str);
+    /// Append `c` escaped as needed inside a JSON string
+    fn push_escaped(&mut self, c: char);
+    /// Remove a trailing `,` if the output ends with one
+    fn pop_comma(&mut self);
+}
+
+impl Output for String {
+    #[inline]
+    fn push(&mut self, c: char) {
+        String::push(self, c);
+    }
+
+    #[inline]
+    fn push_str(&mut self, s: &str) {
+        String::push_str(self, s);
+    }
+
+    #[inline]
+    fn push_escaped(&mut self, c: char) {
+        push_escaped_char(self, c);
+    }
+
+    #[inline]
+    fn pop_comma(&mut self) {
+        if self.ends_with(',') {
+            self.pop();
+        }
+    }
+}
+
+/// Output that only tracks the byte length it would have
+#[derive(Debug, Default)]
+pub(crate) struct SizeCounter {
+    pub len: usize,
+    trailing_comma: bool,
+    scratch: String,
+}
+
+impl Output for SizeCounter {
+    #[inline]
+    fn push(&mut self, c: char) {
+        self.len += c.len_utf8();
+        self.trailing_comma = c == ',';
+    }
+
+    #[inline]
+    fn push_str(&mut self, s: &str) {
+        if let Some(last) = s.chars().next_back() {
+            self.len += s.len();
+            self.trailing_comma = last == ',';
+        }
+    }
+
+    fn push_escaped(&mut self, c: char) {
+        self.scratch.clear();
+        push_escaped_char(&mut self.scratch, c);
+        self.len += self.scratch.len();
+        self.trailing_comma = false;
+    }
+
+    #[inline]
+    fn pop_comma(&mut self) {
+        if self.trailing_comma {
+            self.len -= 1;
+            self.trailing_comma = false;
+        }
+    }
+}
+
+/// Output written straight into a fixed byte buffer
+///
+/// Bytes that do not fit are dropped but still counted, so `len` is the
+/// full output size either way.
+#[derive(Debug)]
+pub(crate) struct SliceOutput<'a> {
+    buf: &'a mut [u8],
+    pub len: usize,
+    trailing_comma: bool,
+    scratch: String,
+}
+
+impl<'a> SliceOutput<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0, trailing_comma: false, scratch: String::new() }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let end = self.len + bytes.len();
+        if let Some(dest) = self.buf.get_mut(self.len..end) {
+            dest.copy_from_slice(bytes);
+        }
+        self.len = end;
+    }
+}
+
+impl Output for SliceOutput<'_> {
+    #[inline]
+    fn push(&mut self, c: char) {
+        self.write(c.encode_utf8(&mut [0; 4]).as_bytes());
+        self.trailing_comma = c == ',';
+    }
+
+    #[inline]
+    fn push_str(&mut self, s: &str) {
+        if let Some(last) = s.chars().next_back() {
+            self.write(s.as_bytes());
+            self.trailing_comma = last == ',';
+        }
+    }
+
+    fn push_escaped(&mut self, c: char) {
+        let mut scratch = std::mem::take(&mut self.scratch);
+        scratch.clear();
+        push_escaped_char(&mut scratch, c);
+        self.write(scratch.as_bytes());
+        self.scratch = scratch;
+        self.trailing_comma = false;
+    }
+
+    #[inline]
+    fn pop_comma(&mut self) {
+        if self.trailing_comma {
+            self.len -= 1;
+            self.trailing_comma = false;
+        }
+    }
+}
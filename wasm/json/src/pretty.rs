@@ -0,0 +1,503 @@
+//! Pretty printing
+//!
+//! `format_preserving` re-indents a document while keeping the lexical form
+//! of every number and double-quoted string exactly as it appears in the
+//! source (sliced via token `start`/`end`), so "format on save" produces
+//! minimal diffs. Only structural whitespace changes; unquoted keys are still
+//! quoted and trailing commas still dropped so the result is valid output.
+//!
+//! `format_jsonc` uses the same layout but also carries comments over from
+//! the whitespace gaps between tokens.
+//!
+//! `fill_layout` is the width-aware layout behind the `fill_width` cleaner
+//! option: a container stays on one line when it fits, otherwise it is
+//! broken one entry per line and its children get the same choice.
+
+use molt_core::*;
+use crate::options::{CleanOptions, Dialect};
+use crate::{find_comment, push_quoted, tokenize};
+
+/// Indentation written once per nesting level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// The given number of spaces
+    Spaces(usize),
+    /// One tab character
+    Tabs,
+}
+
+impl Default for IndentStyle {
+    fn default() -> Self {
+        IndentStyle::Spaces(2)
+    }
+}
+
+impl From<usize> for IndentStyle {
+    fn from(spaces: usize) -> Self {
+        IndentStyle::Spaces(spaces)
+    }
+}
+
+/// Indenting writer shared by the pretty printers
+pub(crate) struct Printer {
+    pub out: String,
+    indent: IndentStyle,
+    depth: usize,
+}
+
+impl Printer {
+    pub fn new(capacity: usize, indent: IndentStyle) -> Self {
+        Printer {
+            out: String::with_capacity(capacity),
+            indent,
+            depth: 0,
+        }
+    }
+
+    /// Start a new line at the current depth
+    pub fn newline(&mut self) {
+        self.out.push('\n');
+        match self.indent {
+            IndentStyle::Spaces(n) => {
+                for _ in 0..self.depth * n {
+                    self.out.push(' ');
+                }
+            }
+            IndentStyle::Tabs => {
+                for _ in 0..self.depth {
+                    self.out.push('\t');
+                }
+            }
+        }
+    }
+
+    pub fn open(&mut self, c: char) {
+        self.out.push(c);
+        self.depth += 1;
+        self.newline();
+    }
+
+    pub fn close(&mut self, c: char) {
+        self.depth = self.depth.saturating_sub(1);
+        self.newline();
+        self.out.push(c);
+    }
+}
+
+/// Re-indent a document, keeping number and string source text byte-for-byte
+///
+/// `indent` is an `IndentStyle` or a number of spaces.
+pub fn format_preserving(
+    input: &str,
+    indent: impl Into<IndentStyle>,
+    options: &CleanOptions,
+) -> Result<String, ParseError> {
+    let tokens = tokenize(input, options)?;
+    let mut printer = Printer::new(input.len() + input.len() / 2, indent.into());
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let next = tokens.get(i + 1).map(|t| t.token_type);
+
+        match token.token_type {
+            TokenType::LeftBrace | TokenType::LeftBracket => {
+                let (open, close, closing) = if token.token_type == TokenType::LeftBrace {
+                    ('{', '}', TokenType::RightBrace)
+                } else {
+                    ('[', ']', TokenType::RightBracket)
+                };
+                if next == Some(closing) {
+                    // Empty containers stay on one line
+                    printer.out.push(open);
+                    printer.out.push(close);
+                    i += 1;
+                } else {
+                    printer.open(open);
+                }
+            }
+            TokenType::RightBrace => printer.close('}'),
+            TokenType::RightBracket => printer.close(']'),
+            TokenType::Colon => printer.out.push_str(": "),
+            TokenType::Comma => {
+                // Drop trailing commas
+                if !matches!(
+                    next,
+                    Some(TokenType::RightBrace | TokenType::RightBracket | TokenType::EOF) | None
+                ) {
+                    printer.out.push(',');
+                    printer.newline();
+                }
+            }
+            TokenType::EOF => break,
+            _ => push_scalar(&mut printer.out, input, token, options),
+        }
+
+        i += 1;
+    }
+
+    Ok(printer.out)
+}
+
+/// Lay out compact JSON so each container fits in `max_width` columns
+///
+/// A container whose single-line form (with a space after each `,` and `:`)
+/// fits on the current line, counting a following comma, is kept inline;
+/// otherwise it is broken one entry per line with two-space indentation.
+/// Scalars are never split, so a long string can still overflow.
+pub(crate) fn fill_layout(compact: &str, max_width: usize) -> String {
+    let mut layout = FillLayout {
+        src: compact,
+        printer: Printer::new(compact.len() * 2, IndentStyle::default()),
+        line_start: 0,
+        max_width,
+    };
+    let mut pos = 0;
+    while pos < compact.len() {
+        pos = layout.value(pos);
+    }
+    layout.printer.out
+}
+
+/// State for `fill_layout`
+struct FillLayout<'a> {
+    src: &'a str,
+    printer: Printer,
+    /// Byte offset in the output where the current line starts
+    line_start: usize,
+    max_width: usize,
+}
+
+impl FillLayout<'_> {
+    /// Write the value starting at `start`, returning the position after it
+    fn value(&mut self, start: usize) -> usize {
+        let bytes = self.src.as_bytes();
+        match bytes[start] {
+            b'{' | b'[' => {
+                let end = container_end(bytes, start);
+                let inline = spaced(&self.src[start..end]);
+                let trailing = usize::from(bytes.get(end) == Some(&b','));
+                let column = self.printer.out[self.line_start..].chars().count();
+                if end - start == 2 || column + inline.chars().count() + trailing <= self.max_width {
+                    self.printer.out.push_str(&inline);
+                } else {
+                    self.broken(start, end);
+                }
+                end
+            }
+            _ => {
+                // A stray top-level `,`, `:`, `]` or `}` is copied on its own
+                let end = scalar_end(bytes, start).max(start + 1);
+                self.printer.out.push_str(&self.src[start..end]);
+                end
+            }
+        }
+    }
+
+    /// Write the container `start..end` one entry per line
+    fn broken(&mut self, start: usize, end: usize) {
+        let bytes = self.src.as_bytes();
+        self.printer.open(bytes[start] as char);
+        self.line_start = self.printer.out.rfind('\n').map_or(0, |i| i + 1);
+        let mut pos = start + 1;
+        while pos < end - 1 {
+            match bytes[pos] {
+                b',' => {
+                    self.printer.out.push(',');
+                    self.printer.newline();
+                    self.line_start = self.printer.out.rfind('\n').map_or(0, |i| i + 1);
+                    pos += 1;
+                }
+                b':' => {
+                    self.printer.out.push_str(": ");
+                    pos += 1;
+                }
+                _ => pos = self.value(pos),
+            }
+        }
+        self.printer.close(bytes[end - 1] as char);
+        self.line_start = self.printer.out.rfind('\n').map_or(0, |i| i + 1);
+    }
+}
+
+/// Position just past the container opening at `start` in compact JSON
+fn container_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut pos = start;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'"' => {
+                pos = scalar_end(bytes, pos);
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return pos + 1;
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+    bytes.len()
+}
+
+/// Position just past the string, number or literal at `start`
+fn scalar_end(bytes: &[u8], start: usize) -> usize {
+    if bytes[start] != b'"' {
+        return bytes[start..]
+            .iter()
+            .position(|b| matches!(b, b',' | b':' | b']' | b'}'))
+            .map_or(bytes.len(), |len| start + len);
+    }
+    let mut pos = start + 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'\\' => pos += 2,
+            b'"' => return pos + 1,
+            _ => pos += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Single-line form of compact JSON with a space after each `,` and `:`
+fn spaced(compact: &str) -> String {
+    let bytes = compact.as_bytes();
+    let mut out = String::with_capacity(compact.len() * 2);
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'"' => {
+                let end = scalar_end(bytes, pos);
+                out.push_str(&compact[pos..end]);
+                pos = end;
+                continue;
+            }
+            b',' => out.push_str(", "),
+            b':' => out.push_str(": "),
+            _ => {
+                // Copy the whole run up to the next structural byte or string
+                let end = bytes[pos..]
+                    .iter()
+                    .position(|b| matches!(b, b'"' | b',' | b':'))
+                    .map_or(bytes.len(), |len| pos + len);
+                out.push_str(&compact[pos..end]);
+                pos = end;
+                continue;
+            }
+        }
+        pos += 1;
+    }
+    out
+}
+
+/// Pretty-print JSONC, keeping comments
+///
+/// Output follows the `format_preserving` layout. Each comment is attached to
+/// the value that follows it and written on its own line at that value's
+/// indentation; comments before a closing bracket stay inside the container.
+/// Line comments between a key and its value are rewritten as block comments
+/// so they can stay on the key's line; a `*/` in their text becomes `* /` so
+/// it cannot end the block early.
+pub fn format_jsonc(
+    input: &str,
+    indent: impl Into<IndentStyle>,
+    options: &CleanOptions,
+) -> Result<String, ParseError> {
+    let tokens = tokenize(input, options)?;
+    let mut printer = Printer::new(input.len() + input.len() / 2, indent.into());
+    let mut pending: Vec<&str> = Vec::new();
+    let mut prev: Option<TokenType> = None;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let gap_start = if i == 0 { 0 } else { tokens[i - 1].end };
+        collect_comments(&input[gap_start..token.start.max(gap_start)], options, &mut pending);
+
+        match token.token_type {
+            // Comments before separators move on to the next value
+            TokenType::Comma | TokenType::Colon => {}
+            TokenType::RightBrace | TokenType::RightBracket | TokenType::EOF => {
+                let at_line_start = matches!(
+                    prev,
+                    Some(TokenType::LeftBrace | TokenType::LeftBracket)
+                ) || printer.out.is_empty();
+                for (n, comment) in pending.drain(..).enumerate() {
+                    if n > 0 || !at_line_start {
+                        printer.newline();
+                    }
+                    printer.out.push_str(comment);
+                }
+            }
+            _ if prev == Some(TokenType::Colon) => {
+                for comment in pending.drain(..) {
+                    match comment.strip_prefix("//").or_else(|| comment.strip_prefix('#')) {
+                        Some(text) => {
+                            printer.out.push_str("/* ");
+                            printer.out.push_str(&text.trim().replace("*/", "* /"));
+                            printer.out.push_str(" */");
+                        }
+                        None => printer.out.push_str(comment),
+                    }
+                    printer.out.push(' ');
+                }
+            }
+            _ => {
+                for comment in pending.drain(..) {
+                    printer.out.push_str(comment);
+                    printer.newline();
+                }
+            }
+        }
+
+        match token.token_type {
+            TokenType::LeftBrace | TokenType::LeftBracket => {
+                let (open, close, closing) = if token.token_type == TokenType::LeftBrace {
+                    ('{', '}', TokenType::RightBrace)
+                } else {
+                    ('[', ']', TokenType::RightBracket)
+                };
+                let inner_gap = tokens
+                    .get(i + 1)
+                    .map_or("", |next| &input[token.end..next.start.max(token.end)]);
+                let next = tokens.get(i + 1).map(|t| t.token_type);
+                let has_comment = find_comment(inner_gap.as_bytes(), options.comment_styles).is_some();
+                if next == Some(closing) && !has_comment {
+                    // Empty containers stay on one line
+                    printer.out.push(open);
+                    printer.out.push(close);
+                    i += 1;
+                } else {
+                    printer.open(open);
+                }
+            }
+            TokenType::RightBrace => printer.close('}'),
+            TokenType::RightBracket => printer.close(']'),
+            TokenType::Colon => printer.out.push_str(": "),
+            TokenType::Comma => {
+                let next = tokens.get(i + 1).map(|t| t.token_type);
+                // Drop trailing commas
+                if !matches!(
+                    next,
+                    Some(TokenType::RightBrace | TokenType::RightBracket | TokenType::EOF) | None
+                ) {
+                    printer.out.push(',');
+                    printer.newline();
+                }
+            }
+            TokenType::EOF => break,
+            _ => push_scalar(&mut printer.out, input, token, options),
+        }
+
+        prev = Some(tokens[i].token_type);
+        i += 1;
+    }
+
+    Ok(printer.out)
+}
+
+/// Append the comments found in a whitespace-and-comments gap
+fn collect_comments<'a>(gap: &'a str, options: &CleanOptions, comments: &mut Vec<&'a str>) {
+    let bytes = gap.as_bytes();
+    let mut pos = 0;
+
+    while let Some(offset) = find_comment(&bytes[pos..], options.comment_styles) {
+        let start = pos + offset;
+        let end = if bytes[start] == b'/' && bytes[start + 1] == b'*' {
+            gap[start + 2..].find("*/").map_or(gap.len(), |close| start + 2 + close + 2)
+        } else {
+            gap[start..].find('\n').map_or(gap.len(), |newline| start + newline)
+        };
+        comments.push(gap[start..end].trim_end());
+        pos = end;
+    }
+}
+
+/// Append a scalar or key token, keeping source text where possible
+fn push_scalar(out: &mut String, input: &str, token: &Token, options: &CleanOptions) {
+    match token.token_type {
+        TokenType::String => {
+            let source = token.span().slice(input);
+            if source.starts_with('"') || options.dialect == Dialect::Json5 {
+                out.push_str(source);
+            } else {
+                push_quoted(out, &token.value);
+            }
+        }
+        TokenType::Number => {
+            if options.dialect == Dialect::Json5 {
+                out.push_str(token.span().slice(input));
+            } else {
+                out.push_str(&token.value);
+            }
+        }
+        TokenType::True => out.push_str("true"),
+        TokenType::False => out.push_str("false"),
+        TokenType::Null => out.push_str("null"),
+        _ => push_quoted(out, &token.value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_preserving_layout() {
+        let input = "{a: [1, 2,], b: {}, c: []}";
+        let result = format_preserving(input, 2, &CleanOptions::default()).unwrap();
+        assert_eq!(result, "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {},\n  \"c\": []\n}");
+    }
+
+    #[test]
+    fn test_format_preserving_keeps_text() {
+        let input = r#"{"n":1.0,"s":"a\"b\u0041\/"}"#;
+        let result = format_preserving(input, 2, &CleanOptions::default()).unwrap();
+        assert_eq!(result, "{\n  \"n\": 1.0,\n  \"s\": \"a\\\"b\\u0041\\/\"\n}");
+
+        let json5 = CleanOptions { dialect: Dialect::Json5, ..Default::default() };
+        let result = format_preserving("[0xFF, +1, 'x']", 1, &json5).unwrap();
+        assert_eq!(result, "[\n 0xFF,\n +1,\n 'x'\n]");
+    }
+
+    #[test]
+    fn test_indent_styles() {
+        let input = "{a: {b: [1]}, c: {}}";
+        let spaces = format_preserving(input, IndentStyle::Spaces(4), &CleanOptions::default()).unwrap();
+        assert_eq!(
+            spaces,
+            "{\n    \"a\": {\n        \"b\": [\n            1\n        ]\n    },\n    \"c\": {}\n}"
+        );
+
+        let tabs = format_preserving(input, IndentStyle::Tabs, &CleanOptions::default()).unwrap();
+        assert_eq!(tabs, "{\n\t\"a\": {\n\t\t\"b\": [\n\t\t\t1\n\t\t]\n\t},\n\t\"c\": {}\n}");
+    }
+
+    #[test]
+    fn test_fill_layout() {
+        let compact = r#"{"short":[1,2,3],"long":["alpha","beta","gamma","delta"],"nested":{"a":[]}}"#;
+        assert_eq!(
+            fill_layout(compact, 30),
+            "{\n  \"short\": [1, 2, 3],\n  \"long\": [\n    \"alpha\",\n    \"beta\",\n    \"gamma\",\n    \"delta\"\n  ],\n  \"nested\": {\"a\": []}\n}"
+        );
+        assert_eq!(fill_layout(r#"[1,{"k":"a,b:c"}]"#, 80), r#"[1, {"k": "a,b:c"}]"#);
+    }
+
+    #[test]
+    fn test_format_jsonc() {
+        let input = "// config\n{\n      // server port\n  port: 8080, /* legacy */ host: 'x',\n\n  list: [1, // one\n 2, // trailing\n  ],\n  empty: { /* none */ },\n  key: // inline\n    true,\n}\n// end\n";
+        let result = format_jsonc(input, 2, &CleanOptions::default()).unwrap();
+        assert_eq!(
+            result,
+            "// config\n{\n  // server port\n  \"port\": 8080,\n  /* legacy */\n  \"host\": \"x\",\n  \"list\": [\n    1,\n    // one\n    2\n    // trailing\n  ],\n  \"empty\": {\n    /* none */\n  },\n  \"key\": /* inline */ true\n}\n// end"
+        );
+
+        let result = format_jsonc("{a: // x */ y\n 1}", 2, &CleanOptions::default()).unwrap();
+        assert_eq!(result, "{\n  \"a\": /* x * / y */ 1\n}");
+        assert_eq!(crate::clean_with_options(&result, &CleanOptions::default()).unwrap(), r#"{"a":1}"#);
+    }
+}
@@ -7,6 +7,9 @@
 #[cfg(target_arch = "wasm32")]
 use std::arch::wasm32::*;
 
+use molt_core::{comment_starts_at, CommentStyles, Span};
+use serde::Serialize;
+
 /// Find all structural character positions using SIMD
 ///
 /// Processes input 16 bytes at a time using WASM SIMD instructions.
@@ -50,6 +53,10 @@ pub fn find_structural_positions_simd(input: &[u8]) -> Vec<usize> {
     positions
 }
 
+/// Bytes reported by the structural scanners
+#[cfg(target_arch = "wasm32")]
+const STRUCTURAL_BYTES: &[u8] = b"{}[]:,\"'";
+
 /// Find structural characters in a 16-byte SIMD vector
 ///
 /// Returns a bitmask where each bit indicates if the corresponding byte
@@ -57,29 +64,7 @@ pub fn find_structural_positions_simd(input: &[u8]) -> Vec<usize> {
 #[cfg(target_arch = "wasm32")]
 #[inline(always)]
 unsafe fn find_structural_mask(chunk: v128) -> u16 {
-    // Create comparison vectors for each structural character
-    let brace_open = i8x16_eq(chunk, i8x16_splat(b'{' as i8));
-    let brace_close = i8x16_eq(chunk, i8x16_splat(b'}' as i8));
-    let bracket_open = i8x16_eq(chunk, i8x16_splat(b'[' as i8));
-    let bracket_close = i8x16_eq(chunk, i8x16_splat(b']' as i8));
-    let colon = i8x16_eq(chunk, i8x16_splat(b':' as i8));
-    let comma = i8x16_eq(chunk, i8x16_splat(b',' as i8));
-    let quote = i8x16_eq(chunk, i8x16_splat(b'"' as i8));
-    let single_quote = i8x16_eq(chunk, i8x16_splat(b'\'' as i8));
-
-    // Combine all masks with OR operations
-    let structural1 = v128_or(
-        v128_or(brace_open, brace_close),
-        v128_or(bracket_open, bracket_close),
-    );
-    let structural2 = v128_or(
-        v128_or(colon, comma),
-        v128_or(quote, single_quote),
-    );
-    let structural = v128_or(structural1, structural2);
-
-    // Extract bitmask
-    i8x16_bitmask(structural)
+    molt_core::simd_find_bytes(chunk, STRUCTURAL_BYTES)
 }
 
 /// Fallback for non-WASM targets
@@ -141,10 +126,27 @@ impl StructType {
     }
 }
 
+/// Byte-range replacement applied to indexed input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edit {
+    /// Offset where the replaced range starts
+    pub start: usize,
+    /// Number of bytes removed at `start`
+    pub removed: usize,
+    /// Number of bytes inserted in their place
+    pub inserted: usize,
+}
+
 /// Structural index for fast token extraction
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StructuralIndex {
     pub positions: Vec<usize>,
     pub types: Vec<StructType>,
+    /// Comments outside strings, recorded by `build_with_comments`
+    pub comments: Vec<Span>,
+    comment_styles: Option<CommentStyles>,
+    /// Offsets where the comment scan resumed after each string or comment
+    comment_bounds: Vec<usize>,
 }
 
 impl StructuralIndex {
@@ -156,7 +158,138 @@ impl StructuralIndex {
             .filter_map(|&pos| StructType::from_byte(input[pos]))
             .collect();
 
-        StructuralIndex { positions, types }
+        StructuralIndex {
+            positions,
+            types,
+            comments: Vec::new(),
+            comment_styles: None,
+            comment_bounds: Vec::new(),
+        }
+    }
+
+    /// Build the index and also record the spans of comments in `styles`
+    ///
+    /// Strings are skipped using the indexed quotes, so only the gaps between
+    /// strings are searched. Structural characters inside comments stay in
+    /// the index; stage 2 can drop those falling within a comment span.
+    pub fn build_with_comments(input: &[u8], styles: CommentStyles) -> Self {
+        let mut index = Self::build(input);
+        index.scan_comments(input, styles, 0, |_| false);
+        index.comment_styles = Some(styles);
+        index
+    }
+
+    /// Patch the index after `edit` was applied, producing `input`
+    ///
+    /// Only the inserted bytes are re-scanned; entries after the edit are
+    /// shifted by the length delta. No margin is needed around the edit:
+    /// stage 1 classifies each byte on its own, and whether a quote or comma
+    /// sits inside a string or comment is decided later by stage 2.
+    ///
+    /// Comments are re-scanned from the last string or comment boundary
+    /// before the edit until the scan lines up with the old one again. An
+    /// edit that opens or closes a string shifts every later boundary, so it
+    /// re-scans to the end of the input.
+    pub fn update(&mut self, input: &[u8], edit: Edit) {
+        let old_end = edit.start + edit.removed;
+        let new_end = edit.start + edit.inserted;
+        let first = self.positions.partition_point(|&pos| pos < edit.start);
+        let last = self.positions.partition_point(|&pos| pos < old_end);
+
+        for pos in &mut self.positions[last..] {
+            *pos = *pos - edit.removed + edit.inserted;
+        }
+
+        let (positions, types): (Vec<usize>, Vec<StructType>) = input[edit.start..new_end]
+            .iter()
+            .enumerate()
+            .filter_map(|(offset, &byte)| {
+                StructType::from_byte(byte).map(|typ| (edit.start + offset, typ))
+            })
+            .unzip();
+        self.positions.splice(first..last, positions);
+        self.types.splice(first..last, types);
+
+        if let Some(styles) = self.comment_styles {
+            let keep = self.comment_bounds.partition_point(|&bound| bound < edit.start);
+            let resume = keep.checked_sub(1).map_or(0, |i| self.comment_bounds[i]);
+            let old_bounds = self.comment_bounds.split_off(keep);
+            let old_comments = self.comments.split_off(self.comments.partition_point(|c| c.end <= resume));
+
+            // Past the edit, a bound the old scan also resumed at means the
+            // rest of the old scan still holds
+            let to_old = |pos: usize| pos + edit.removed - edit.inserted;
+            let rejoined = self.scan_comments(input, styles, resume, |cursor| {
+                cursor >= new_end && old_bounds.binary_search(&to_old(cursor)).is_ok()
+            });
+            if let Some(cursor) = rejoined {
+                let shift = |pos: usize| pos - edit.removed + edit.inserted;
+                let old_cursor = to_old(cursor);
+                self.comments.extend(
+                    old_comments
+                        .into_iter()
+                        .filter(|c| c.start >= old_cursor)
+                        .map(|c| Span::new(shift(c.start), shift(c.end))),
+                );
+                self.comment_bounds.extend(old_bounds.into_iter().filter(|&b| b > old_cursor).map(shift));
+            }
+        }
+    }
+
+    /// Find comments outside strings using the indexed quotes
+    ///
+    /// Scanning starts at `cursor`, which must not be inside a string or
+    /// comment. Each comment is appended to `comments`, and the offset after
+    /// each string or comment to `comment_bounds`. Stops at the first bound
+    /// `stop` accepts and returns it, or returns `None` at the end of the input.
+    fn scan_comments(
+        &mut self,
+        input: &[u8],
+        styles: CommentStyles,
+        mut cursor: usize,
+        mut stop: impl FnMut(usize) -> bool,
+    ) -> Option<usize> {
+        let Self { positions, types, comments, comment_bounds: bounds, .. } = self;
+        let len = input.len();
+        let first = positions.partition_point(|&pos| pos < cursor);
+        let mut quotes = positions[first..]
+            .iter()
+            .zip(&types[first..])
+            .filter(|(_, typ)| matches!(typ, StructType::Quote | StructType::SingleQuote))
+            .map(|(&pos, &typ)| (pos, typ))
+            .peekable();
+
+        loop {
+            // Quotes already passed (inside a string or comment) are not openers
+            while quotes.next_if(|&(pos, _)| pos < cursor).is_some() {}
+            let gap_end = quotes.peek().map_or(len, |&(pos, _)| pos);
+
+            if let Some(start) = (cursor..gap_end).find(|&pos| comment_starts_at(input, pos, len, styles)) {
+                let end = if input[start] == b'/' && input[start + 1] == b'*' {
+                    input[start + 2..]
+                        .windows(2)
+                        .position(|w| w == b"*/")
+                        .map_or(len, |offset| start + 2 + offset + 2)
+                } else {
+                    input[start..].iter().position(|&b| b == b'\n').map_or(len, |offset| start + offset)
+                };
+                comments.push(Span::new(start, end));
+                cursor = end;
+            } else {
+                let (open, typ) = quotes.next()?;
+                let close = quotes.by_ref().find(|&(pos, t)| t == typ && !is_escaped_quote(input, open, pos));
+                cursor = close.map_or(len, |(pos, _)| pos + 1);
+            }
+
+            // One running to the end of the input may still be open, which
+            // makes no safe point to resume from
+            if cursor < len {
+                bounds.push(cursor);
+                if stop(cursor) {
+                    return Some(cursor);
+                }
+            }
+        }
     }
 
     /// Get the number of structural characters
@@ -182,20 +315,286 @@ impl StructuralIndex {
     }
 }
 
+/// Summary of the dirty-JSON features present in an input; see `quick_scan`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanResult {
+    /// Every string (double- or single-quoted) is closed
+    pub balanced_quotes: bool,
+    /// A `//` or `/* */` comment appears outside strings
+    pub has_comments: bool,
+    /// A single-quoted string appears
+    pub has_single_quotes: bool,
+    /// Deepest bracket nesting reached
+    pub max_depth: usize,
+}
+
+/// Pre-check input for dirty-JSON features without tokenizing it
+///
+/// One pass over the structural index: quotes toggle string state (escaped
+/// quotes and quotes inside comments are skipped) and brackets outside
+/// strings count nesting depth. Brackets are not matched against each other.
+pub fn quick_scan(input: &[u8]) -> ScanResult {
+    let styles = CommentStyles::DOUBLE_SLASH.union(CommentStyles::SLASH_STAR);
+    let index = StructuralIndex::build_with_comments(input, styles);
+    let mut result = ScanResult {
+        has_comments: !index.comments.is_empty(),
+        ..ScanResult::default()
+    };
+    let mut comments = index.comments.iter().peekable();
+    let mut open_quote: Option<(usize, StructType)> = None;
+    let mut depth = 0usize;
+
+    for (&pos, &typ) in index.positions.iter().zip(&index.types) {
+        while comments.next_if(|comment| comment.end <= pos).is_some() {}
+        if open_quote.is_none() && comments.peek().is_some_and(|comment| comment.start <= pos) {
+            continue;
+        }
+
+        match (open_quote, typ) {
+            (Some((open, quote)), _) => {
+                if typ == quote && !is_escaped_quote(input, open, pos) {
+                    open_quote = None;
+                }
+            }
+            (None, StructType::Quote | StructType::SingleQuote) => {
+                result.has_single_quotes |= typ == StructType::SingleQuote;
+                open_quote = Some((pos, typ));
+            }
+            (None, StructType::BraceOpen | StructType::BracketOpen) => {
+                depth += 1;
+                result.max_depth = result.max_depth.max(depth);
+            }
+            (None, StructType::BraceClose | StructType::BracketClose) => {
+                depth = depth.saturating_sub(1);
+            }
+            (None, _) => {}
+        }
+    }
+
+    result.balanced_quotes = open_quote.is_none();
+    result
+}
+
+/// Whether the quote at `pos` is escaped by an odd run of backslashes after `open`
+fn is_escaped_quote(input: &[u8], open: usize, pos: usize) -> bool {
+    let backslashes = input[open + 1..pos].iter().rev().take_while(|&&b| b == b'\\').count();
+    backslashes % 2 == 1
+}
+
+/// Length of the run of number characters (`[0-9.eE+-]`) starting at `start`
+///
+/// Scans 16 bytes at a time with SIMD, stopping at `end`.
+#[cfg(target_arch = "wasm32")]
+pub fn number_run_length(input: &[u8], start: usize, end: usize) -> usize {
+    let mut pos = start;
+
+    while pos + 16 <= end {
+        let mask = unsafe {
+            let chunk = v128_load(input.as_ptr().add(pos) as *const v128);
+            find_number_mask(chunk)
+        };
+
+        if mask != 0xFFFF {
+            return pos + (!mask).trailing_zeros() as usize - start;
+        }
+        pos += 16;
+    }
+
+    pos - start + number_run_length_scalar(input, pos, end)
+}
+
+/// Find number characters in a 16-byte SIMD vector
+#[cfg(target_arch = "wasm32")]
+#[inline(always)]
+unsafe fn find_number_mask(chunk: v128) -> u16 {
+    let digit = v128_and(
+        u8x16_ge(chunk, u8x16_splat(b'0')),
+        u8x16_le(chunk, u8x16_splat(b'9')),
+    );
+    let dot = i8x16_eq(chunk, i8x16_splat(b'.' as i8));
+    let exp_lower = i8x16_eq(chunk, i8x16_splat(b'e' as i8));
+    let exp_upper = i8x16_eq(chunk, i8x16_splat(b'E' as i8));
+    let plus = i8x16_eq(chunk, i8x16_splat(b'+' as i8));
+    let minus = i8x16_eq(chunk, i8x16_splat(b'-' as i8));
+
+    let number = v128_or(
+        v128_or(digit, dot),
+        v128_or(v128_or(exp_lower, exp_upper), v128_or(plus, minus)),
+    );
+
+    i8x16_bitmask(number)
+}
+
+/// Fallback for non-WASM targets
+#[cfg(not(target_arch = "wasm32"))]
+pub fn number_run_length(input: &[u8], start: usize, end: usize) -> usize {
+    number_run_length_scalar(input, start, end)
+}
+
+/// Scalar number run scan
+#[inline]
+pub fn number_run_length_scalar(input: &[u8], start: usize, end: usize) -> usize {
+    input[start..end]
+        .iter()
+        .take_while(|&&b| matches!(b, b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-'))
+        .count()
+}
+
+/// Counters describing one run of the SIMD two-stage cleaner
+///
+/// Collected only by `clean_dirty_json_simd_stats`; the regular entry points
+/// don't pay for counting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimdStats {
+    /// Structural characters found by stage 1
+    pub structural_count: usize,
+    /// Full 16-byte chunks classified (with SIMD on wasm32)
+    pub simd_chunks_processed: usize,
+    /// Bytes after the last full chunk, classified one at a time
+    pub scalar_tail_bytes: usize,
+    /// String tokens produced by stage 2
+    pub string_count: usize,
+}
+
+/// Lazy structural character scanner
+///
+/// Yields `(position, type)` pairs in order without materializing a position
+/// vector. Input is classified one 16-byte chunk at a time (SIMD on wasm32),
+/// so memory use is constant regardless of input size.
+pub struct StructuralScanner<'a> {
+    input: &'a [u8],
+    /// Start of the chunk `mask` refers to
+    chunk_start: usize,
+    /// Structural bits of the current chunk not yet yielded
+    mask: u16,
+    /// Start of the next chunk to classify
+    next_chunk: usize,
+    /// Full 16-byte chunks classified so far
+    full_chunks: usize,
+    /// Bytes classified in a final partial chunk
+    tail_bytes: usize,
+}
+
+impl<'a> StructuralScanner<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        StructuralScanner {
+            input,
+            chunk_start: 0,
+            mask: 0,
+            next_chunk: 0,
+            full_chunks: 0,
+            tail_bytes: 0,
+        }
+    }
+
+    /// Full 16-byte chunks classified so far (with SIMD on wasm32)
+    pub fn full_chunks(&self) -> usize {
+        self.full_chunks
+    }
+
+    /// Bytes of a final partial chunk classified so far, one at a time
+    pub fn tail_bytes(&self) -> usize {
+        self.tail_bytes
+    }
+}
+
+impl Iterator for StructuralScanner<'_> {
+    type Item = (usize, StructType);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.mask != 0 {
+                let bit = self.mask.trailing_zeros() as usize;
+                self.mask &= self.mask - 1;
+                let pos = self.chunk_start + bit;
+                return StructType::from_byte(self.input[pos]).map(|typ| (pos, typ));
+            }
+
+            if self.next_chunk >= self.input.len() {
+                return None;
+            }
+
+            self.chunk_start = self.next_chunk;
+            self.mask = chunk_mask(self.input, self.chunk_start);
+            self.next_chunk += 16;
+            match self.input.len() - self.chunk_start {
+                remaining if remaining >= 16 => self.full_chunks += 1,
+                remaining => self.tail_bytes += remaining,
+            }
+        }
+    }
+}
+
+/// Structural bitmask for the (up to) 16 bytes starting at `start`
+#[cfg(target_arch = "wasm32")]
+#[inline(always)]
+fn chunk_mask(input: &[u8], start: usize) -> u16 {
+    if start + 16 <= input.len() {
+        unsafe {
+            let chunk = v128_load(input.as_ptr().add(start) as *const v128);
+            find_structural_mask(chunk)
+        }
+    } else {
+        chunk_mask_scalar(input, start)
+    }
+}
+
+/// Structural bitmask for the (up to) 16 bytes starting at `start`
+#[cfg(not(target_arch = "wasm32"))]
+#[inline(always)]
+fn chunk_mask(input: &[u8], start: usize) -> u16 {
+    chunk_mask_scalar(input, start)
+}
+
+/// Scalar bitmask fallback
+#[inline(always)]
+fn chunk_mask_scalar(input: &[u8], start: usize) -> u16 {
+    let end = (start + 16).min(input.len());
+    let mut mask = 0u16;
+
+    for (bit, &byte) in input[start..end].iter().enumerate() {
+        if is_structural_char(byte) {
+            mask |= 1 << bit;
+        }
+    }
+
+    mask
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_quick_scan() {
+        let clean = quick_scan(br#"{"a": [1, {"b": "x\"]}"}], "c": "//"}"#);
+        assert_eq!(
+            clean,
+            ScanResult { balanced_quotes: true, has_comments: false, has_single_quotes: false, max_depth: 3 }
+        );
+
+        let dirty = quick_scan(b"// it's [\n{a: 'x', /* \" */ b: [[1]]}");
+        assert_eq!(
+            dirty,
+            ScanResult { balanced_quotes: true, has_comments: true, has_single_quotes: true, max_depth: 3 }
+        );
+
+        assert!(!quick_scan(br#"{"a": "open}"#).balanced_quotes);
+        assert!(!quick_scan(b"['it]").balanced_quotes);
+    }
+
     #[test]
     fn test_find_structural_positions() {
         let input = br#"{"name":"alice","age":30}"#;
         let positions = find_structural_positions_simd(input);
 
-        // Should find: { " : " , " : } = 10 structural chars
-        assert_eq!(positions.len(), 10);
+        // Should find: { " " : " " , " " : } = 11 structural chars
+        assert_eq!(positions.len(), 11);
         assert_eq!(input[positions[0]], b'{');
         assert_eq!(input[positions[1]], b'"');
-        assert_eq!(input[positions[9]], b'}');
+        assert_eq!(input[positions[10]], b'}');
     }
 
     #[test]
@@ -203,9 +602,114 @@ mod tests {
         let input = br#"{"key":"value"}"#;
         let index = StructuralIndex::build(input);
 
-        assert_eq!(index.len(), 6); // { " : " }
+        assert_eq!(index.len(), 7); // { " " : " " }
         assert_eq!(index.types[0], StructType::BraceOpen);
-        assert_eq!(index.types[5], StructType::BraceClose);
+        assert_eq!(index.types[6], StructType::BraceClose);
+    }
+
+    #[test]
+    fn test_index_update() {
+        let before = br#"{"a": [1, 2], "b": "x,y"}"#;
+        let mut index = StructuralIndex::build(before);
+
+        // Insert `"q", ` before `2`, then replace `"x,y"` with `{}`
+        let after = br#"{"a": [1, "q", 2], "b": "x,y"}"#;
+        index.update(after, Edit { start: 10, removed: 0, inserted: 5 });
+        assert_eq!(index, StructuralIndex::build(after));
+
+        let edited = br#"{"a": [1, "q", 2], "b": {}}"#;
+        index.update(edited, Edit { start: 24, removed: 5, inserted: 2 });
+        assert_eq!(index, StructuralIndex::build(edited));
+    }
+
+    #[test]
+    fn test_index_comment_spans() {
+        let input = br#"{"url": "http://x", // first
+  'a': 1, /* second, "quoted" */ "b": "/*"}"#;
+        let index = StructuralIndex::build_with_comments(input, CommentStyles::default());
+        assert_eq!(index.comments, vec![Span::new(20, 28), Span::new(39, 61)]);
+        assert_eq!(&input[39..61], br#"/* second, "quoted" */"#);
+        assert!(StructuralIndex::build(input).comments.is_empty());
+    }
+
+    #[test]
+    fn test_index_update_comments() {
+        let before = br#"{"a": 1, // one
+  "b": "x", /* two */ "c": [3]} # three"#;
+        let styles = CommentStyles::default().union(CommentStyles::HASH);
+        // Inside a line comment, opening a string that swallows a block
+        // comment, closing it again, ending a block comment early, and
+        // extending the unterminated comment at the end
+        let edits: [(usize, usize, &[u8]); 5] = [
+            (12, 3, b"uno, 'q'"),
+            (32, 0, b"\""),
+            (32, 1, b""),
+            (36, 3, b"*/ x"),
+            (61, 0, b" //"),
+        ];
+
+        let mut input = before.to_vec();
+        let mut index = StructuralIndex::build_with_comments(&input, styles);
+        for (start, removed, replacement) in edits {
+            input.splice(start..start + removed, replacement.iter().copied());
+            index.update(&input, Edit { start, removed, inserted: replacement.len() });
+            assert_eq!(index, StructuralIndex::build_with_comments(&input, styles));
+        }
+    }
+
+    #[test]
+    fn test_scanner_matches_index() {
+        let input = br#"{"name": "alice", "tags": ['a', 'b'], "nested": {"deep": [1, 2, 3]}}"#;
+        let index = StructuralIndex::build(input);
+        let scanned: Vec<(usize, StructType)> = StructuralScanner::new(input).collect();
+
+        assert_eq!(scanned.len(), index.len());
+        for (i, item) in scanned.iter().enumerate() {
+            assert_eq!(Some(*item), index.get(i));
+        }
+    }
+
+    #[test]
+    fn test_number_run_length() {
+        let input = b"[-1.25e+10, 3, 12345678901234567890.5 ]";
+        assert_eq!(number_run_length(input, 1, input.len()), 9);
+        assert_eq!(number_run_length(input, 12, input.len()), 1);
+        assert_eq!(number_run_length(input, 15, input.len()), 22);
+        assert_eq!(number_run_length(input, 15, 20), 5);
+        for start in 0..input.len() {
+            assert_eq!(
+                number_run_length(input, start, input.len()),
+                number_run_length_scalar(input, start, input.len())
+            );
+        }
+    }
+
+    // The test below exercises the SIMD scan and needs a wasm32 runner with
+    // simd128, e.g. `RUSTFLAGS="-C target-feature=+simd128" cargo test
+    // -p molt-json-wasm --target wasm32-wasip1` with wasmtime set as
+    // `CARGO_TARGET_WASM32_WASIP1_RUNNER`.
+
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn test_number_run_length_simd_matches_scalar() {
+        // Every byte value at every offset of the first chunks
+        let mut input = vec![b'7'; 40];
+        for offset in 0..input.len() {
+            for byte in 0..=u8::MAX {
+                input[offset] = byte;
+                for start in 0..3 {
+                    assert_eq!(
+                        number_run_length(&input, start, input.len()),
+                        number_run_length_scalar(&input, start, input.len()),
+                        "byte {} at {}, start {}",
+                        byte,
+                        offset,
+                        start
+                    );
+                }
+            }
+            input[offset] = b'7';
+        }
     }
 
     #[test]
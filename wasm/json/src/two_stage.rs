@@ -6,100 +6,123 @@
 //! This approach minimizes branching and enables better CPU pipelining.
 
 use molt_core::*;
-use crate::simd::{StructuralIndex, StructType};
+use crate::number::{check_range, scan_number};
+use crate::options::{CleanOptions, KeySeparators};
+use crate::simd::{SimdStats, StructType, StructuralScanner};
 
 /// Parse dirty JSON using two-stage approach
-pub fn parse_two_stage(input: &str) -> Result<Vec<Token>, ParseError> {
-    // Stage 1: Build structural index (SIMD-accelerated)
-    let index = StructuralIndex::build(input.as_bytes());
-
-    // Stage 2: Extract tokens from index
-    extract_tokens(input.as_bytes(), &index)
+pub fn parse_two_stage(input: &str, options: &CleanOptions) -> Result<Vec<Token>, ParseError> {
+    parse_two_stage_bytes(input.as_bytes(), options)
 }
 
-/// Extract tokens from structural index
-fn extract_tokens(input: &[u8], index: &StructuralIndex) -> Result<Vec<Token>, ParseError> {
-    let mut tokens = Vec::with_capacity(index.len());
-    let mut i = 0;
+/// Parse dirty JSON bytes using two-stage approach
+///
+/// String contents are validated as UTF-8; invalid sequences are a
+/// `ParseError` at the offending byte unless `options.lossy_utf8` is set.
+/// Options the extractor doesn't implement fall back to the scalar
+/// tokenizer; see `needs_scalar_tokenizer`.
+pub fn parse_two_stage_bytes(input: &[u8], options: &CleanOptions) -> Result<Vec<Token>, ParseError> {
+    if needs_scalar_tokenizer(options) {
+        return scalar_tokens(input, options);
+    }
 
-    while i < index.len() {
-        let (pos, typ) = index.get(i).unwrap();
+    // Stage 1: Scan structural characters lazily (SIMD-accelerated)
+    let scanner = StructuralScanner::new(input);
 
-        match typ {
-            StructType::Quote | StructType::SingleQuote => {
-                // Find matching closing quote
-                let string_token = extract_string(input, index, i, typ)?;
-                tokens.push(string_token.0);
-                i = string_token.1; // Jump to position after closing quote
-            }
+    // Stage 2: Extract tokens as structural characters stream by
+    extract_tokens(input, scanner, options)
+}
 
-            StructType::BraceOpen => {
-                tokens.push(Token::new(
-                    TokenType::LeftBrace,
-                    String::new(),
-                    pos,
-                    pos + 1,
-                ));
-                i += 1;
-            }
+/// Parse dirty JSON using two-stage approach, counting the work done
+///
+/// Chunks and tail bytes are counted by the scanner as it classifies them;
+/// structural characters are counted as stage 2 consumes them.
+pub fn parse_two_stage_stats(
+    input: &str,
+    options: &CleanOptions,
+) -> Result<(Vec<Token>, SimdStats), ParseError> {
+    let bytes = input.as_bytes();
+    if needs_scalar_tokenizer(options) {
+        // No chunks are scanned on the scalar path
+        let tokens = scalar_tokens(bytes, options)?;
+        let string_count = tokens.iter().filter(|t| t.token_type == TokenType::String).count();
+        return Ok((tokens, SimdStats { string_count, ..SimdStats::default() }));
+    }
 
-            StructType::BraceClose => {
-                tokens.push(Token::new(
-                    TokenType::RightBrace,
-                    String::new(),
-                    pos,
-                    pos + 1,
-                ));
-                i += 1;
-            }
+    let mut structural_count = 0;
+    let mut scanner = StructuralScanner::new(bytes);
+    let structurals = (&mut scanner).inspect(|_| structural_count += 1);
+    let tokens = extract_tokens(bytes, structurals, options)?;
+
+    let stats = SimdStats {
+        structural_count,
+        simd_chunks_processed: scanner.full_chunks(),
+        scalar_tail_bytes: scanner.tail_bytes(),
+        string_count: tokens
+            .iter()
+            .filter(|t| t.token_type == TokenType::String)
+            .count(),
+    };
+    Ok((tokens, stats))
+}
 
-            StructType::BracketOpen => {
-                tokens.push(Token::new(
-                    TokenType::LeftBracket,
-                    String::new(),
-                    pos,
-                    pos + 1,
-                ));
-                i += 1;
-            }
+/// Whether `options` ask for behavior only the scalar tokenizer implements
+///
+/// `reject_comments` has to see each comment where it starts, and `=>` and
+/// `=` separators from `key_separators` are not structural characters.
+fn needs_scalar_tokenizer(options: &CleanOptions) -> bool {
+    options.reject_comments || options.key_separators != KeySeparators::default()
+}
 
-            StructType::BracketClose => {
-                tokens.push(Token::new(
-                    TokenType::RightBracket,
-                    String::new(),
-                    pos,
-                    pos + 1,
-                ));
-                i += 1;
-            }
+/// Tokenize with the scalar tokenizer
+fn scalar_tokens(input: &[u8], options: &CleanOptions) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::with_capacity(input.len() / 8);
+    crate::tokenize_bytes_into(input, None, options, &mut tokens)?;
+    Ok(tokens)
+}
 
-            StructType::Colon => {
-                tokens.push(Token::new(
-                    TokenType::Colon,
-                    String::new(),
-                    pos,
-                    pos + 1,
-                ));
-                i += 1;
-            }
+/// Extract tokens from a stream of structural characters
+fn extract_tokens<I>(
+    input: &[u8],
+    structurals: I,
+    options: &CleanOptions,
+) -> Result<Vec<Token>, ParseError>
+where
+    I: Iterator<Item = (usize, StructType)>,
+{
+    let mut structurals = structurals.peekable();
+    let mut tokens = Vec::with_capacity(input.len() / 8);
+
+    while let Some((pos, typ)) = structurals.next() {
+        let token_type = match typ {
+            StructType::Quote | StructType::SingleQuote => None,
+            StructType::BraceOpen => Some(TokenType::LeftBrace),
+            StructType::BraceClose => Some(TokenType::RightBrace),
+            StructType::BracketOpen => Some(TokenType::LeftBracket),
+            StructType::BracketClose => Some(TokenType::RightBracket),
+            StructType::Colon => Some(TokenType::Colon),
+            StructType::Comma => Some(TokenType::Comma),
+        };
 
-            StructType::Comma => {
-                tokens.push(Token::new(
-                    TokenType::Comma,
-                    String::new(),
-                    pos,
-                    pos + 1,
-                ));
-                i += 1;
+        let end = match token_type {
+            Some(token_type) => {
+                tokens.push(Token::new(token_type, String::new(), pos, pos + 1));
+                pos + 1
             }
-        }
+            None => {
+                // Consume structural characters up to the matching closing quote
+                let string_token =
+                    extract_string(input, &mut structurals, pos, typ, options.lossy_utf8)?;
+                let end = string_token.end;
+                tokens.push(string_token);
+                end
+            }
+        };
 
         // Extract non-structural tokens between structural characters
-        if i < index.len() {
-            let next_pos = index.positions[i];
-            if next_pos > pos + 1 {
-                // There's content between structural chars
-                extract_value_tokens(input, pos + 1, next_pos, &mut tokens)?;
+        if let Some(&(next_pos, _)) = structurals.peek() {
+            if next_pos > end {
+                extract_value_tokens(input, end, next_pos, options, &mut tokens)?;
             }
         }
     }
@@ -110,73 +133,70 @@ fn extract_tokens(input: &[u8], index: &StructuralIndex) -> Result<Vec<Token>, P
 
 /// Extract string token from input
 ///
-/// Finds the matching closing quote and extracts the string content.
-/// Returns (token, next_index_position)
-fn extract_string(
+/// Consumes structural characters until the matching unescaped closing quote
+/// and extracts the string content.
+fn extract_string<I>(
     input: &[u8],
-    index: &StructuralIndex,
-    start_idx: usize,
+    structurals: &mut I,
+    start_pos: usize,
     quote_type: StructType,
-) -> Result<(Token, usize), ParseError> {
-    let start_pos = index.positions[start_idx];
-
-    // Find matching closing quote
-    let mut i = start_idx + 1;
-    let mut escaped = false;
-
-    while i < index.len() {
-        let (pos, typ) = index.get(i).unwrap();
-
-        // Check for escape sequences
-        if pos > start_pos + 1 {
-            let prev_byte = input[pos - 1];
-            if prev_byte == b'\\' {
-                // Check if the backslash itself is escaped
-                let mut backslash_count = 0;
-                let mut check_pos = pos - 1;
-                while check_pos > start_pos && input[check_pos] == b'\\' {
-                    backslash_count += 1;
-                    if check_pos == 0 {
-                        break;
-                    }
-                    check_pos -= 1;
-                }
-                escaped = backslash_count % 2 == 1;
-            } else {
-                escaped = false;
-            }
-        }
-
+    lossy_utf8: bool,
+) -> Result<Token, ParseError>
+where
+    I: Iterator<Item = (usize, StructType)>,
+{
+    for (pos, typ) in structurals {
         // Found matching quote?
-        if typ == quote_type && !escaped {
+        if typ == quote_type && !is_escaped(input, start_pos, pos) {
             // Extract string content
             let content_start = start_pos + 1;
             let content_end = pos;
-            let value = String::from_utf8_lossy(&input[content_start..content_end]).to_string();
-
-            return Ok((
-                Token::new(TokenType::String, value, start_pos, pos + 1),
-                i + 1,
-            ));
+            let content = &input[content_start..content_end];
+            let value = if lossy_utf8 {
+                String::from_utf8_lossy(content).to_string()
+            } else {
+                std::str::from_utf8(content)
+                    .map_err(|e| {
+                        ParseError::new(
+                            "Invalid UTF-8 in string",
+                            content_start + e.valid_up_to(),
+                        )
+                    })?
+                    .to_string()
+            };
+
+            return Ok(Token::new(TokenType::String, value, start_pos, pos + 1));
         }
-
-        i += 1;
     }
 
     Err(ParseError::new("Unterminated string", start_pos))
 }
 
+/// Check whether the byte at `pos` is preceded by an odd run of backslashes
+/// (stopping at the opening quote at `start_pos`)
+#[inline]
+fn is_escaped(input: &[u8], start_pos: usize, pos: usize) -> bool {
+    let mut backslash_count = 0;
+    let mut check_pos = pos;
+    while check_pos > start_pos + 1 && input[check_pos - 1] == b'\\' {
+        backslash_count += 1;
+        check_pos -= 1;
+    }
+    backslash_count % 2 == 1
+}
+
 /// Extract value tokens (numbers, keywords, identifiers) between structural positions
 fn extract_value_tokens(
     input: &[u8],
     start: usize,
     end: usize,
+    options: &CleanOptions,
     tokens: &mut Vec<Token>,
 ) -> Result<(), ParseError> {
     let mut pos = start;
 
     // Skip whitespace and comments
-    pos = skip_whitespace_and_comments_range(input, pos, end);
+    pos = skip_whitespace_and_comments_with(input, pos, end, options.comment_styles);
 
     if pos >= end {
         return Ok(());
@@ -186,52 +206,9 @@ fn extract_value_tokens(
 
     // Numbers
     if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
-        let mut value = String::new();
-
-        // Skip leading +
-        if c == '+' {
-            pos += 1;
-            if pos >= end {
-                return Ok(());
-            }
-        } else {
-            value.push(c);
-            pos += 1;
-        }
-
-        // Hex numbers
-        if pos < end && value == "0" && input[pos] == b'x' {
-            value.push('x');
-            pos += 1;
-
-            let hex_start = pos;
-            while pos < end && (input[pos] as char).is_ascii_hexdigit() {
-                value.push(input[pos] as char);
-                pos += 1;
-            }
-
-            if pos == hex_start {
-                return Err(ParseError::new("Invalid hex number", start));
-            }
-
-            // Convert hex to decimal
-            if let Ok(hex_val) = u64::from_str_radix(&value[2..], 16) {
-                value = hex_val.to_string();
-            }
-        } else {
-            // Regular numbers
-            while pos < end {
-                let ch = input[pos] as char;
-                if ch.is_ascii_digit() || ch == '.' || ch == 'e' || ch == 'E' || ch == '+' || ch == '-' {
-                    value.push(ch);
-                    pos += 1;
-                } else {
-                    break;
-                }
-            }
-        }
-
-        tokens.push(Token::new(TokenType::Number, value, start, pos));
+        let (token_type, value, end) = scan_number(input, pos, end, options)?;
+        check_range(&value, pos, options.overflow_numbers)?;
+        tokens.push(Token::new(token_type, value, pos, end));
         return Ok(());
     }
 
@@ -261,47 +238,6 @@ fn extract_value_tokens(
     Ok(())
 }
 
-/// Skip whitespace and comments in a range
-fn skip_whitespace_and_comments_range(input: &[u8], start: usize, end: usize) -> usize {
-    let mut pos = start;
-
-    while pos < end {
-        let c = input[pos];
-
-        // Whitespace
-        if c.is_ascii_whitespace() {
-            pos += 1;
-            continue;
-        }
-
-        // Single-line comments
-        if c == b'/' && pos + 1 < end && input[pos + 1] == b'/' {
-            pos += 2;
-            while pos < end && input[pos] != b'\n' {
-                pos += 1;
-            }
-            continue;
-        }
-
-        // Multi-line comments
-        if c == b'/' && pos + 1 < end && input[pos + 1] == b'*' {
-            pos += 2;
-            while pos + 1 < end {
-                if input[pos] == b'*' && input[pos + 1] == b'/' {
-                    pos += 2;
-                    break;
-                }
-                pos += 1;
-            }
-            continue;
-        }
-
-        break;
-    }
-
-    pos
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -309,7 +245,7 @@ mod tests {
     #[test]
     fn test_parse_simple() {
         let input = r#"{"name":"alice","age":30}"#;
-        let tokens = parse_two_stage(input).unwrap();
+        let tokens = parse_two_stage(input, &CleanOptions::default()).unwrap();
 
         assert!(!tokens.is_empty());
         // Should have: { " identifier " : " string " , " identifier " : number }
@@ -321,14 +257,103 @@ mod tests {
             // Comment
             "name": "bob"
         }"#;
-        let tokens = parse_two_stage(input).unwrap();
+        let tokens = parse_two_stage(input, &CleanOptions::default()).unwrap();
         assert!(!tokens.is_empty());
     }
 
+    #[test]
+    fn test_quoted_keys_and_escapes() {
+        let input = r#"{"key": "va\"lue", 'k2': 'it\'s'}"#;
+        let tokens = parse_two_stage(input, &CleanOptions::default()).unwrap();
+        let types: Vec<TokenType> = tokens.iter().map(|t| t.token_type).collect();
+
+        assert_eq!(
+            types,
+            vec![
+                TokenType::LeftBrace,
+                TokenType::String,
+                TokenType::Colon,
+                TokenType::String,
+                TokenType::Comma,
+                TokenType::String,
+                TokenType::Colon,
+                TokenType::String,
+                TokenType::RightBrace,
+                TokenType::EOF,
+            ]
+        );
+        assert_eq!(tokens[3].value, r#"va\"lue"#);
+        assert_eq!(tokens[7].value, r"it\'s");
+    }
+
+    #[test]
+    fn test_invalid_utf8_in_string() {
+        let input = b"{\"a\": \"ok\xFF\xFEbad\"}";
+
+        let err = parse_two_stage_bytes(input, &CleanOptions::default()).unwrap_err();
+        assert_eq!(err.message, "Invalid UTF-8 in string");
+        assert_eq!(err.position, 9);
+
+        let lossy = CleanOptions { lossy_utf8: true, ..Default::default() };
+        let tokens = parse_two_stage_bytes(input, &lossy).unwrap();
+        assert_eq!(tokens[3].value, "ok\u{FFFD}\u{FFFD}bad");
+    }
+
+    #[test]
+    fn test_hash_comments() {
+        let options = CleanOptions {
+            comment_styles: CommentStyles::default() | CommentStyles::HASH,
+            ..Default::default()
+        };
+        let tokens = parse_two_stage("{ a: 1 # comment\n }", &options).unwrap();
+        assert_eq!(tokens.len(), 6); // { a : 1 } EOF
+    }
+
+    #[test]
+    fn test_reject_comments() {
+        let strict = CleanOptions { reject_comments: true, ..Default::default() };
+        let err = parse_two_stage("{a: 1 // note\n}", &strict).unwrap_err();
+        assert_eq!((err.message.as_str(), err.position), ("Comments are not allowed", 6));
+        assert!(parse_two_stage(r#"{"u":"http://x"}"#, &strict).is_ok());
+
+        let err = parse_two_stage_stats("[1, /* x */ 2]", &strict).unwrap_err();
+        assert_eq!(err.position, 4);
+    }
+
+    #[test]
+    fn test_key_separators() {
+        let options = CleanOptions { key_separators: KeySeparators::ALL, ..Default::default() };
+        let input = "{a => 1, b = 'x', c: [2]}";
+        let tokens = parse_two_stage(input, &options).unwrap();
+        assert_eq!(tokens.iter().filter(|t| t.token_type == TokenType::Colon).count(), 3);
+        assert_eq!(crate::reconstruct_json(&tokens, &options).unwrap(), r#"{"a":1,"b":"x","c":[2]}"#);
+    }
+
     #[test]
     fn test_parse_dirty() {
         let input = "{ name: 'alice', age: 30, }";
-        let tokens = parse_two_stage(input).unwrap();
+        let tokens = parse_two_stage(input, &CleanOptions::default()).unwrap();
         assert!(!tokens.is_empty());
     }
+
+    #[test]
+    fn test_stats() {
+        let input = r#"{"name":"alice","age":30,"tags":['a','b']}"#;
+        let (tokens, stats) = parse_two_stage_stats(input, &CleanOptions::default()).unwrap();
+        assert_eq!(tokens.len(), parse_two_stage(input, &CleanOptions::default()).unwrap().len());
+        assert_eq!(
+            stats,
+            SimdStats {
+                structural_count: 22,
+                simd_chunks_processed: 2,
+                scalar_tail_bytes: 10,
+                string_count: 6,
+            }
+        );
+
+        let (_, stats) = parse_two_stage_stats("[1]", &CleanOptions::default()).unwrap();
+        assert_eq!((stats.simd_chunks_processed, stats.scalar_tail_bytes), (0, 3));
+        let (_, stats) = parse_two_stage_stats(&" ".repeat(32), &CleanOptions::default()).unwrap();
+        assert_eq!((stats.simd_chunks_processed, stats.scalar_tail_bytes), (2, 0));
+    }
 }
@@ -0,0 +1,55 @@
+//! Unicode normalization of string content
+//!
+//! Only compiled with the `unicode-normalize` feature so the normalization
+//! tables are linked only when asked for.
+
+use std::borrow::Cow;
+
+use molt_core::*;
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+
+/// Unicode normalization form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NormForm {
+    /// Canonical composition (`e` + U+0301 becomes `é`)
+    Nfc,
+    /// Canonical decomposition (`é` becomes `e` + U+0301)
+    Nfd,
+}
+
+/// Normalize the decoded text of a raw string token value
+///
+/// Values with escape sequences are decoded first so escaped combining marks
+/// are normalized too, then re-escaped. ASCII-only values are returned as-is.
+pub fn normalize_raw(raw: &str, form: NormForm) -> Cow<'_, str> {
+    if raw.is_ascii() && !raw.contains("\\u") {
+        return Cow::Borrowed(raw);
+    }
+
+    let Ok(decoded) = unescape_json_string(raw) else {
+        return Cow::Borrowed(raw);
+    };
+    let normalized: String = match form {
+        NormForm::Nfc => decoded.nfc().collect(),
+        NormForm::Nfd => decoded.nfd().collect(),
+    };
+    if normalized == decoded && !raw.contains('\\') {
+        return Cow::Borrowed(raw);
+    }
+    Cow::Owned(escape_json_string(&normalized))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_raw() {
+        assert_eq!(normalize_raw("cafe\u{301}", NormForm::Nfc), "caf\u{e9}");
+        assert_eq!(normalize_raw("cafe\\u0301", NormForm::Nfc), "caf\u{e9}");
+        assert_eq!(normalize_raw("caf\u{e9}", NormForm::Nfd), "cafe\u{301}");
+        assert!(matches!(normalize_raw("plain", NormForm::Nfc), Cow::Borrowed(_)));
+    }
+}
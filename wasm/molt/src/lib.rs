@@ -0,0 +1,127 @@
+//! Unified molt entry point
+//!
+//! Detects the format of arbitrary text and dispatches to the matching
+//! cleaner or parser, always producing JSON. See `molt_core::detect_format`
+//! for the detection heuristics and their order.
+
+use molt_core::*;
+use molt_csv_wasm::{parse_csv_with, stringify_csv_with, ParseOptions, StringifyOptions};
+use molt_json_wasm::{clean_with_options, ndjson_to_array, CleanOptions};
+use wasm_bindgen::prelude::*;
+
+/// Detect the format of the input
+///
+/// Returns one of `"json"`, `"ndjson"`, `"csv"` or `"unknown"`.
+#[wasm_bindgen]
+pub fn detect_input_format(input: &str) -> String {
+    detect_format(input).as_str().to_string()
+}
+
+/// Infer the CSV dialect of the input
+///
+/// Returns a JSON object such as
+/// `{"delimiter":";","quote":"'","quotedFields":true,"lineTerminator":"\r\n"}`.
+#[wasm_bindgen]
+pub fn detect_csv_dialect(input: &str) -> String {
+    serde_json::to_string(&detect_dialect(input)).unwrap_or_default()
+}
+
+/// Convert any supported text format to JSON
+#[wasm_bindgen]
+pub fn molt(input: &str) -> Result<String, JsValue> {
+    molt_auto(input).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Detect the input format and convert it to JSON
+///
+/// - JSON is cleaned with the dirty JSON cleaner
+/// - NDJSON is cleaned line by line and returned as a JSON array
+/// - CSV is parsed with the detected delimiter and a header row
+pub fn molt_auto(input: &str) -> Result<String, String> {
+    let options = CleanOptions::default();
+
+    match detect_format(input) {
+        Format::Json => clean_with_options(input, &options).map_err(|e| e.to_string()),
+        Format::Ndjson => ndjson_to_array(input).map_err(|e| e.to_string()),
+        Format::Csv => {
+            let options = ParseOptions {
+                delimiter: detect_delimiter(input).unwrap_or(','),
+                ..ParseOptions::default()
+            };
+            parse_csv_with(input, &options)
+        }
+        Format::Unknown => Err("Unable to detect input format".to_string()),
+    }
+}
+
+/// Convert NDJSON records of varying shape into one CSV table
+///
+/// # Arguments
+/// * `input` - NDJSON text, one (possibly dirty) object per line
+/// * `delimiter` - Field delimiter (default: ',')
+#[wasm_bindgen]
+pub fn ndjson_to_csv(input: &str, delimiter: Option<char>) -> Result<String, JsValue> {
+    ndjson_to_csv_with(input, delimiter.unwrap_or(',')).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Clean each NDJSON line and write the records as CSV
+///
+/// The columns are the union of keys across all records in first-seen
+/// order; a record missing a key gets an empty cell. A record that isn't an
+/// object is an error naming its 1-based record number.
+pub fn ndjson_to_csv_with(input: &str, delimiter: char) -> Result<String, String> {
+    let array = ndjson_to_array(input).map_err(|e| e.to_string())?;
+    let data: serde_json::Value =
+        serde_json::from_str(&array).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    let records = data.as_array().map(Vec::as_slice).unwrap_or_default();
+    if let Some(index) = records.iter().position(|record| !record.is_object()) {
+        return Err(format!("Record {} is not an object", index + 1));
+    }
+
+    let options = StringifyOptions { delimiter, ..StringifyOptions::default() };
+    stringify_csv_with(&data, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_molt_auto_json() {
+        assert_eq!(molt_auto("{a: 1,}").unwrap(), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn test_molt_auto_ndjson() {
+        let input = "{a: 1}\n{a: 2}\n";
+        assert_eq!(molt_auto(input).unwrap(), r#"[{"a":1},{"a":2}]"#);
+    }
+
+    #[test]
+    fn test_molt_auto_csv() {
+        let input = "name;age\nAlice;30";
+        assert_eq!(molt_auto(input).unwrap(), r#"[{"name":"Alice","age":30}]"#);
+        assert!(molt_auto("plain text").is_err());
+    }
+
+    #[test]
+    fn test_ndjson_to_csv() {
+        let input = "{id: 1, name: 'a'}\n{id: 2, level: 'warn',}\n\n{name: 'c', extra: true}\n";
+        assert_eq!(
+            ndjson_to_csv_with(input, ',').unwrap(),
+            "id,name,level,extra\n1,a,,\n2,,warn,\n,c,,true"
+        );
+
+        let err = ndjson_to_csv_with("{a: 1}\n[2]", ',').unwrap_err();
+        assert_eq!(err, "Record 2 is not an object");
+    }
+
+    #[test]
+    fn test_detect_csv_dialect() {
+        assert_eq!(
+            detect_csv_dialect("a;b\r\n1;2\r\n"),
+            r#"{"delimiter":";","quote":"\"","quotedFields":false,"lineTerminator":"\r\n"}"#
+        );
+    }
+}